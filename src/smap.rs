@@ -3,50 +3,310 @@
 //! Contains the `StorageMap`; a feature-gated map structure that alternates between stack and heap
 //! storage depending on the `alloc` feature.
 
+#[cfg(not(feature = "alloc"))]
+use core::mem;
 #[cfg(not(feature = "alloc"))]
 use tinymap::{TinyMap, TinyMapIterator};
+#[cfg(not(feature = "alloc"))]
+use tinyvec::{ArrayVec, ArrayVecIterator};
 
+#[cfg(feature = "ordered")]
+use alloc::collections::{btree_map as map, BTreeMap};
 #[cfg(feature = "alloc")]
-use core::marker::PhantomData;
+use alloc::vec::Vec;
+#[cfg(all(feature = "alloc", not(feature = "ordered")))]
+use hashbrown::{hash_map as map, HashMap};
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserialize, Deserializer, MapAccess, Visitor},
+    ser::{Serialize, Serializer},
+};
+
+#[cfg(not(feature = "alloc"))]
+use core::hash::BuildHasherDefault;
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash, Hasher},
+    iter,
+    marker::PhantomData,
+    ops,
+};
+
+/// The default hash builder used by `StorageMap`. When the `alloc` feature is enabled, this is
+/// hashbrown's default hash builder.
 #[cfg(feature = "alloc")]
-use hashbrown::HashMap;
+pub type DefaultHashBuilder = hashbrown::hash_map::DefaultHashBuilder;
+
+/// The default hash builder used by `StorageMap`. When the `alloc` feature is disabled, keys are
+/// never hashed, so this is only a placeholder.
+#[cfg(not(feature = "alloc"))]
+pub type DefaultHashBuilder = BuildHasherDefault<FnvHasher>;
 
-use core::{fmt, hash::Hash, iter};
+macro_rules! storage_key {
+    ($($bound: tt)*) => {
+        /// The bound placed on the keys of a `StorageMap`. Every backend needs `Eq` and `Ord`,
+        /// but only the `HashMap` backend hashes its keys, so this only requires `Hash` when the
+        /// `alloc` feature is enabled without the `ordered` feature. It is implemented for every
+        /// type that meets the bound.
+        ///
+        /// As with `StorageElement`, libraries that want to work with any backend should still
+        /// require `Hash` on their key types.
+        pub trait StorageKey: $($bound)* {}
+
+        impl<T: ?Sized + $($bound)*> StorageKey for T {}
+    };
+}
+
+#[cfg(all(feature = "alloc", not(feature = "ordered")))]
+storage_key!(Eq + Ord + Hash);
+
+#[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+storage_key!(Eq + Ord);
 
 /// A map object that with either use the tinymap `TinyMap` or the hashbrown `HashMap` as a
-/// backing implementation. It will use the `alloc` feature to control this.
+/// backing implementation. It will use the `alloc` feature to control this. If the `ordered`
+/// feature is enabled, the standard library `BTreeMap` is used instead of `HashMap`, so
+/// iteration yields entries sorted by key.
+///
+/// The hash builder `S` is only used by the `HashMap` backend. Otherwise, it is accepted but
+/// ignored.
 #[repr(transparent)]
 #[deprecated = "This crate is now deprecated."]
-pub struct StorageMap<K: Eq + Ord + Hash, V, const N: usize>(SMImpl<K, V, N>);
+pub struct StorageMap<K: StorageKey, V, const N: usize, S = DefaultHashBuilder>(SMImpl<K, V, N, S>);
 
-#[cfg(feature = "alloc")]
+#[cfg(all(feature = "alloc", not(feature = "ordered")))]
 #[repr(transparent)]
-struct SMImpl<K: Eq + Ord + Hash, V, const N: usize>(HashMap<K, V>, PhantomData<[V; N]>);
+struct SMImpl<K: StorageKey, V, const N: usize, S>(HashMap<K, V, S>, PhantomData<[V; N]>);
 
-#[cfg(not(feature = "alloc"))]
+#[cfg(feature = "ordered")]
 #[repr(transparent)]
-struct SMImpl<K: Eq + Ord + Hash, V, const N: usize>(TinyMap<K, V, N>);
+struct SMImpl<K: StorageKey, V, const N: usize, S>(BTreeMap<K, V>, PhantomData<([V; N], S)>);
+
+#[cfg(not(feature = "alloc"))]
+struct SMImpl<K: StorageKey, V, const N: usize, S>(TinyMap<K, V, N>, PhantomData<S>);
 
-impl<K: Eq + Ord + Hash, V, const N: usize> StorageMap<K, V, N> {
+impl<K: StorageKey, V, const N: usize, S: BuildHasher + Default> StorageMap<K, V, N, S> {
     /// Create a new, empty `StorageMap`.
+    ///
+    /// This is not a `const fn`, since neither the tinymap nor the hashbrown constructors are
+    /// `const`.
     #[inline]
     #[must_use]
     pub fn new() -> Self {
         Self::new_impl()
     }
 
-    #[cfg(feature = "alloc")]
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn new_impl() -> Self {
+        Self(SMImpl(HashMap::default(), PhantomData))
+    }
+
+    #[cfg(feature = "ordered")]
     #[inline]
     fn new_impl() -> Self {
-        Self(SMImpl(HashMap::new(), PhantomData))
+        Self(SMImpl(BTreeMap::new(), PhantomData))
     }
 
     #[cfg(not(feature = "alloc"))]
     #[inline]
     fn new_impl() -> Self {
-        Self(SMImpl(TinyMap::new()))
+        Self(SMImpl(TinyMap::new(), PhantomData))
+    }
+
+    /// Create a new, empty `StorageMap` that uses `hasher` to hash its keys.
+    ///
+    /// When the `alloc` feature is disabled or the `ordered` feature is enabled, keys are never
+    /// hashed and `hasher` is ignored.
+    #[inline]
+    #[must_use]
+    pub fn with_hasher(hasher: S) -> Self {
+        Self::with_hasher_impl(hasher)
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn with_hasher_impl(hasher: S) -> Self {
+        Self(SMImpl(HashMap::with_hasher(hasher), PhantomData))
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[inline]
+    fn with_hasher_impl(_hasher: S) -> Self {
+        Self::new()
+    }
+
+    /// Create a new, empty `StorageMap` with space for at least `cap` entries.
+    ///
+    /// When the `alloc` feature is disabled, the capacity is always `N` and `cap` is ignored.
+    /// When the `ordered` feature is enabled, there is no capacity and `cap` is ignored.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        Self::with_capacity_impl(cap)
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn with_capacity_impl(cap: usize) -> Self {
+        Self(SMImpl(
+            HashMap::with_capacity_and_hasher(cap, S::default()),
+            PhantomData,
+        ))
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[inline]
+    fn with_capacity_impl(_cap: usize) -> Self {
+        Self::new()
+    }
+
+    /// Create a new, empty `StorageMap` with space for at least `cap` entries that uses
+    /// `hasher` to hash its keys.
+    ///
+    /// When the `alloc` feature is disabled, the capacity is always `N` and both arguments are
+    /// ignored. When the `ordered` feature is enabled, there is no capacity and keys are never
+    /// hashed, so both arguments are ignored as well.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity_and_hasher(cap: usize, hasher: S) -> Self {
+        Self::with_capacity_and_hasher_impl(cap, hasher)
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn with_capacity_and_hasher_impl(cap: usize, hasher: S) -> Self {
+        Self(SMImpl(
+            HashMap::with_capacity_and_hasher(cap, hasher),
+            PhantomData,
+        ))
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[inline]
+    fn with_capacity_and_hasher_impl(_cap: usize, _hasher: S) -> Self {
+        Self::new()
+    }
+
+    /// Get the number of entries this map can hold. When the `alloc` feature is disabled, this
+    /// is always `N`. When the `ordered` feature is enabled, every entry is allocated as needed,
+    /// so this is the length of the map. Otherwise, it is the number of entries that can be held
+    /// without reallocating.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity_impl()
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn capacity_impl(&self) -> usize {
+        (self.0).0.capacity()
+    }
+
+    #[cfg(feature = "ordered")]
+    #[inline]
+    fn capacity_impl(&self) -> usize {
+        self.len()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn capacity_impl(&self) -> usize {
+        N
+    }
+
+    /// Get the number of entries that can still be inserted before this map is full. When the
+    /// `alloc` feature is enabled, the map grows as needed and this returns `usize::MAX`.
+    ///
+    /// When the map is full, `insert` panics; use `try_insert` to handle this case instead.
+    #[inline]
+    #[must_use]
+    pub fn remaining_capacity(&self) -> usize {
+        self.remaining_capacity_impl()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn remaining_capacity_impl(&self) -> usize {
+        usize::MAX
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn remaining_capacity_impl(&self) -> usize {
+        N - self.len()
+    }
+
+    /// Tell whether this map is full, so that inserting a new key would fail. When the `alloc`
+    /// feature is enabled, the map grows as needed and this always returns `false`.
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.remaining_capacity() == 0
+    }
+
+    /// Reserve space for at least `additional` more entries.
+    ///
+    /// When the `alloc` feature is disabled, the capacity is fixed at `N` and this does nothing.
+    /// This also does nothing when the `ordered` feature is enabled.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.reserve_impl(additional);
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn reserve_impl(&mut self, additional: usize) {
+        (self.0).0.reserve(additional);
     }
 
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn reserve_impl(&mut self, _additional: usize) {}
+
+    /// Shrink the capacity of this map as much as possible. When the `alloc` feature is
+    /// disabled or the `ordered` feature is enabled, this does nothing.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit_impl();
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn shrink_to_fit_impl(&mut self) {
+        (self.0).0.shrink_to_fit();
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn shrink_to_fit_impl(&mut self) {}
+
+    /// Shrink the capacity of this map, keeping it at least as large as both its length and
+    /// `min_capacity`. When the `alloc` feature is disabled or the `ordered` feature is enabled,
+    /// this does nothing.
+    #[inline]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.shrink_to_impl(min_capacity);
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn shrink_to_impl(&mut self, min_capacity: usize) {
+        (self.0).0.shrink_to(min_capacity);
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn shrink_to_impl(&mut self, _min_capacity: usize) {}
+
     /// Get the length of this storage map.
     #[inline]
     #[must_use]
@@ -61,6 +321,41 @@ impl<K: Eq + Ord + Hash, V, const N: usize> StorageMap<K, V, N> {
         (self.0).0.is_empty()
     }
 
+    /// Remove every entry from this map. Any heap capacity is retained.
+    #[inline]
+    pub fn clear(&mut self) {
+        (self.0).0.clear();
+    }
+
+    /// Hash `key` the way this map would, so that the hash can be computed once and cached.
+    /// Equal keys always produce equal hashes for the same map.
+    ///
+    /// Only the `HashMap` backend hashes its keys with `S`. Otherwise, the key is hashed with
+    /// `FnvHasher`.
+    #[inline]
+    #[must_use]
+    pub fn hash_key(&self, key: &K) -> u64
+    where
+        K: Hash,
+    {
+        let mut hasher = self.key_hasher();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn key_hasher(&self) -> S::Hasher {
+        (self.0).0.hasher().build_hasher()
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn key_hasher(&self) -> FnvHasher {
+        FnvHasher::default()
+    }
+
     /// Get an element from this map by its key.
     #[inline]
     #[must_use]
@@ -68,6 +363,50 @@ impl<K: Eq + Ord + Hash, V, const N: usize> StorageMap<K, V, N> {
         (self.0).0.get(key)
     }
 
+    /// Get an element from this map by any borrowed form of its key, such as a `&str` for a
+    /// `String` key.
+    ///
+    /// When the `alloc` feature is disabled, `TinyMap` can only search by `&K`, so this compares
+    /// the key against every entry in turn and takes O(n) time. Use `get` when a `&K` is at
+    /// hand.
+    #[inline]
+    #[must_use]
+    pub fn get_borrowed<Q: ?Sized + StorageKey>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_borrowed_impl(key)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn get_borrowed_impl<Q: ?Sized + StorageKey>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        (self.0).0.get(key)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn get_borrowed_impl<Q: ?Sized + StorageKey>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        (self.0)
+            .0
+            .iter()
+            .find(|(k, _)| (*k).borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Get an element from this map by its key, or `default` if the key is not present.
+    #[inline]
+    #[must_use]
+    pub fn get_or<'a>(&'a self, key: &K, default: &'a V) -> &'a V {
+        self.get(key).unwrap_or(default)
+    }
+
     /// Get a mutable reference to an element by its key.
     #[inline]
     #[must_use]
@@ -75,6 +414,99 @@ impl<K: Eq + Ord + Hash, V, const N: usize> StorageMap<K, V, N> {
         (self.0).0.get_mut(key)
     }
 
+    /// Get a mutable reference to an element by any borrowed form of its key.
+    ///
+    /// When the `alloc` feature is disabled, this has the same O(n) cost as `get_borrowed`.
+    #[inline]
+    #[must_use]
+    pub fn get_mut_borrowed<Q: ?Sized + StorageKey>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_mut_borrowed_impl(key)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn get_mut_borrowed_impl<Q: ?Sized + StorageKey>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        (self.0).0.get_mut(key)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn get_mut_borrowed_impl<Q: ?Sized + StorageKey>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        (self.0)
+            .0
+            .iter_mut()
+            .find(|(k, _)| (*k).borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Get mutable references to the elements corresponding to several keys at once. This
+    /// returns `None` if any of the keys are not in the map, or if any key is repeated.
+    ///
+    /// With the `HashMap` backend, every key is looked up by hash first, so a missing key is
+    /// found in O(M) time. hashbrown has no way to borrow several values by key at once, so
+    /// once every key is known to be present, the values are still collected in one pass over
+    /// the map. With the other backends, that pass is the whole lookup, and it takes O(n * M)
+    /// time.
+    #[inline]
+    #[must_use]
+    pub fn get_many_mut<const M: usize>(&mut self, keys: [&K; M]) -> Option<[&mut V; M]> {
+        if keys
+            .iter()
+            .enumerate()
+            .any(|(i, key)| keys[..i].contains(key))
+        {
+            return None;
+        }
+
+        self.get_many_mut_impl(keys)
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[inline]
+    fn get_many_mut_impl<const M: usize>(&mut self, keys: [&K; M]) -> Option<[&mut V; M]> {
+        if keys.iter().all(|key| (self.0).0.contains_key(*key)) {
+            self.scan_many_mut(keys)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[inline]
+    fn get_many_mut_impl<const M: usize>(&mut self, keys: [&K; M]) -> Option<[&mut V; M]> {
+        self.scan_many_mut(keys)
+    }
+
+    #[inline]
+    fn scan_many_mut<const M: usize>(&mut self, keys: [&K; M]) -> Option<[&mut V; M]> {
+        let mut values: [Option<&mut V>; M] = [(); M].map(|()| None);
+        let mut found = 0;
+        for (key, value) in self.iter_mut() {
+            if let Some(i) = keys.iter().position(|k| *k == key) {
+                values[i] = Some(value);
+                found += 1;
+                if found == M {
+                    break;
+                }
+            }
+        }
+
+        if found == M {
+            Some(values.map(Option::unwrap))
+        } else {
+            None
+        }
+    }
+
     /// Insert a new element into this map. If the key already exists in the map, it
     /// returns the value previously held in that slot. Otherwise, it will return None.
     ///
@@ -99,123 +531,1630 @@ impl<K: Eq + Ord + Hash, V, const N: usize> StorageMap<K, V, N> {
         (self.0).0.try_insert(key, value)
     }
 
-    /// Insert a new element into this map.
+    /// Insert a new element into this map, but only if it can be done without growing the map.
+    /// If the key already exists in the map, it returns the value previously held in that slot.
+    /// Otherwise, it will return None.
+    ///
+    /// When the `ordered` feature is enabled, every new entry is allocated as needed, so this
+    /// only succeeds if the key already exists.
+    ///
+    /// # Errors
+    ///
+    /// It will return back the key-value pair if the map would have to grow or rehash to hold
+    /// the new entry, or if the insertion cannot be accomplished due to capacity overflow.
+    #[inline]
+    pub fn try_insert_within_capacity(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        if self.len() < self.capacity() || self.contains_key(&key) {
+            self.try_insert(key, value)
+        } else {
+            Err((key, value))
+        }
+    }
+
+    /// Insert a new element into this map, and panic if the insertion fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and the map is full. Use `try_insert` to
+    /// handle this case instead.
     #[inline]
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        (self.0).0.insert(key, value)
+        match self.try_insert(key, value) {
+            Ok(old) => old,
+            Err((_, _)) => panic!("<StorageMap> capacity overflow"),
+        }
     }
 
-    /// Remove a key/value entry from this map.
+    /// Try to insert every key-value pair of `iter` into this map, passing each pair that does
+    /// not fit to `rejected`. Keys that are already in the map always fit, and their values are
+    /// replaced.
+    ///
+    /// When the `alloc` feature is disabled, every pair that arrives after the map is full is
+    /// rejected, and the rest of the iterator is still consumed. Otherwise, the map never fills
+    /// up and `rejected` is never called.
     #[inline]
-    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
-        (self.0).0.remove_entry(key)
+    pub fn insert_many_with<I: IntoIterator<Item = (K, V)>, F: FnMut(K, V)>(
+        &mut self,
+        iter: I,
+        mut rejected: F,
+    ) {
+        for (key, value) in iter {
+            if let Err((key, value)) = self.try_insert(key, value) {
+                rejected(key, value);
+            }
+        }
     }
 
-    /// Remove a value from this map.
+    /// Try to insert every key-value pair of `iter` into this map, and return the pairs that did
+    /// not fit. The map never fills up when the `alloc` feature is enabled, so the returned
+    /// `Vec` is always empty. Use `insert_many_with` when the `alloc` feature is disabled.
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub fn remove(&mut self, key: &K) -> Option<V> {
-        (self.0).0.remove(key)
+    pub fn insert_many<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) -> Vec<(K, V)> {
+        let mut rejects = Vec::new();
+        self.insert_many_with(iter, |key, value| rejects.push((key, value)));
+        rejects
     }
 
-    /// Tell whether this map contains a certain key.
+    /// Get the entry for the given key in this map, for in-place manipulation.
     #[inline]
-    pub fn contains_key(&self, key: &K) -> bool {
-        (self.0).0.contains_key(key)
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, N, S> {
+        self.entry_impl(key)
     }
 
-    /// Get an iterator that iterates over the key-value pairs in arbitrary order.
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        (self.0).0.iter()
+    fn entry_impl(&mut self, key: K) -> Entry<'_, K, V, N, S> {
+        Entry(EntryImpl((self.0).0.entry(key), PhantomData))
     }
 
-    /// Get an iterator that iterates over the key-value pairs in arbitary order, mutably.
+    #[cfg(not(feature = "alloc"))]
     #[inline]
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
-        (self.0).0.iter_mut()
+    fn entry_impl(&mut self, key: K) -> Entry<'_, K, V, N, S> {
+        Entry(EntryImpl {
+            map: &mut (self.0).0,
+            key,
+            hasher: PhantomData,
+        })
     }
 
-    /// Get an iterator that iterates over the keys in arbitrary order.
+    /// Remove a key/value entry from this map.
     #[inline]
-    pub fn keys(&self) -> impl Iterator<Item = &K> {
-        (self.0).0.keys()
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        (self.0).0.remove_entry(key)
     }
 
-    /// Get an iterator that iterates over the values in arbitrary order.
+    /// Remove a key/value entry from this map by any borrowed form of its key.
+    ///
+    /// When the `alloc` feature is disabled, `TinyMap` can only remove by `&K`, so this finds
+    /// the entry by comparing the key against every entry in turn, and then rebuilds the map by
+    /// reinserting every other entry, which takes O(n log n) time. The map is only touched once
+    /// the entry has been found, so a panic while comparing against `key` leaves it unchanged.
+    /// Use `remove_entry` when a `&K` is at hand.
     #[inline]
-    pub fn values(&self) -> impl Iterator<Item = &V> {
-        (self.0).0.values()
+    pub fn remove_entry_borrowed<Q: ?Sized + StorageKey>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry_borrowed_impl(key)
     }
 
-    /// Get an iterator that iterates over the values in arbitrary order, mutably.
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
-        (self.0).0.values_mut()
+    fn remove_entry_borrowed_impl<Q: ?Sized + StorageKey>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        (self.0).0.remove_entry(key)
     }
-}
 
-impl<K: Ord + Eq + Hash + fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug
-    for StorageMap<K, V, N>
-{
+    #[cfg(not(feature = "alloc"))]
     #[inline]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&(self.0).0, f)
+    fn remove_entry_borrowed_impl<Q: ?Sized + StorageKey>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = (self.0).0.iter().position(|(k, _)| k.borrow() == key)?;
+
+        // TinyMap can only remove by an owned key, so rebuild the map without the entry. The
+        // rebuild visits the entries in the same order as `iter`.
+        let mut i = 0;
+        let mut removed = None;
+        self.rebuild(
+            |_, _| {
+                i += 1;
+                i - 1 != index
+            },
+            |k, v| removed = Some((k, v)),
+        );
+        removed
     }
-}
 
-impl<K: Ord + Eq + Hash + Clone, V: Clone, const N: usize> Clone for SMImpl<K, V, N> {
-    #[cfg(feature = "alloc")]
+    /// Remove a value from this map.
     #[inline]
-    fn clone(&self) -> Self {
-        Self(self.0.clone(), PhantomData)
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        (self.0).0.remove(key)
     }
 
-    #[cfg(not(feature = "alloc"))]
+    /// Remove a value from this map by any borrowed form of its key.
+    ///
+    /// When the `alloc` feature is disabled, this has the same cost as `remove_entry_borrowed`.
     #[inline]
-    fn clone(&self) -> Self {
-        Self(self.0.clone())
+    pub fn remove_borrowed<Q: ?Sized + StorageKey>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry_borrowed(key).map(|(_, v)| v)
     }
-}
 
-impl<K: Ord + Eq + Hash + Clone, V: Clone, const N: usize> Clone for StorageMap<K, V, N> {
+    /// Retain only the entries that satisfy the predicate `f`, removing the rest. The predicate
+    /// may also modify the values it is given.
+    ///
+    /// If `f` panics, every entry that it has not rejected is still in the map.
     #[inline]
-    fn clone(&self) -> Self {
-        Self(self.0.clone())
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F) {
+        self.retain_impl(f);
     }
-}
 
-impl<K: Ord + Eq + Hash, V, const N: usize> iter::IntoIterator for StorageMap<K, V, N> {
-    type Item = (K, V);
     #[cfg(feature = "alloc")]
-    type IntoIter = hashbrown::hash_map::IntoIter<K, V>;
+    #[inline]
+    fn retain_impl<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F) {
+        (self.0).0.retain(f);
+    }
+
     #[cfg(not(feature = "alloc"))]
-    type IntoIter = TinyMapIterator<K, V, N>;
+    #[inline]
+    fn retain_impl<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F) {
+        self.rebuild(f, |_, _| {});
+    }
 
+    /// Rebuild this map from the entries for which `keep` returns `true`, and pass the rest to
+    /// `removed`. `TinyMap` doesn't support removal in the middle of iteration, so this is how
+    /// entries are removed by a predicate.
+    #[cfg(not(feature = "alloc"))]
     #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        (self.0).0.into_iter()
+    fn rebuild<F: FnMut(&K, &mut V) -> bool, G: FnMut(K, V)>(
+        &mut self,
+        mut keep: F,
+        mut removed: G,
+    ) {
+        // puts back the entry being looked at and every unvisited entry, even if `keep` panics
+        struct Rebuild<'a, K: Ord, V, const N: usize> {
+            map: &'a mut TinyMap<K, V, N>,
+            current: Option<(K, V)>,
+            rest: TinyMapIterator<K, V, N>,
+        }
+
+        impl<K: Ord, V, const N: usize> Drop for Rebuild<'_, K, V, N> {
+            #[inline]
+            fn drop(&mut self) {
+                let current = self.current.take();
+                self.map.extend(current.into_iter().chain(&mut self.rest));
+            }
+        }
+
+        let old = mem::take(&mut (self.0).0);
+        let mut rebuild = Rebuild {
+            map: &mut (self.0).0,
+            current: None,
+            rest: old.into_iter(),
+        };
+        for entry in rebuild.rest.by_ref() {
+            let (key, value) = rebuild.current.insert(entry);
+            let kept = keep(key, value);
+            if let Some((key, value)) = rebuild.current.take() {
+                if kept {
+                    rebuild.map.insert(key, value);
+                } else {
+                    removed(key, value);
+                }
+            }
+        }
     }
-}
 
-impl<K: Ord + Eq + Hash, V, const N: usize> iter::Extend<(K, V)> for StorageMap<K, V, N> {
+    /// Remove every entry for which `f` returns `true` and get an iterator over the removed
+    /// key/value pairs, in arbitrary order. The predicate may also modify the values it is
+    /// given.
+    ///
+    /// The matching entries are removed before this method returns, so they are removed even
+    /// if the iterator is dropped before it is exhausted. If `f` panics, every entry that it has
+    /// not matched is still in the map.
     #[inline]
-    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
-        (self.0).0.extend(iter);
+    pub fn extract_if<F: FnMut(&K, &mut V) -> bool>(
+        &mut self,
+        f: F,
+    ) -> StorageMapExtractIf<K, V, N> {
+        self.extract_if_impl(f)
     }
-}
 
-impl<K: Ord + Eq + Hash, V, const N: usize> iter::FromIterator<(K, V)> for StorageMap<K, V, N> {
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
     #[inline]
-    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
-        let mut map = Self::new();
-        map.extend(iter);
-        map
+    fn extract_if_impl<F: FnMut(&K, &mut V) -> bool>(
+        &mut self,
+        mut f: F,
+    ) -> StorageMapExtractIf<K, V, N> {
+        // this version of hashbrown drains the entries for which the predicate returns false
+        let extracted: Vec<(K, V)> = (self.0)
+            .0
+            .drain_filter(|key, value| !f(key, value))
+            .collect();
+        StorageMapExtractIf(extracted.into_iter())
     }
-}
 
-impl<K: Ord + Eq + Hash, V, const N: usize> Default for StorageMap<K, V, N> {
+    #[cfg(feature = "ordered")]
     #[inline]
-    fn default() -> Self {
-        Self::new()
+    fn extract_if_impl<F: FnMut(&K, &mut V) -> bool>(
+        &mut self,
+        f: F,
+    ) -> StorageMapExtractIf<K, V, N> {
+        let extracted: Vec<(K, V)> = (self.0).0.extract_if(.., f).collect();
+        StorageMapExtractIf(extracted.into_iter())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn extract_if_impl<F: FnMut(&K, &mut V) -> bool>(
+        &mut self,
+        mut f: F,
+    ) -> StorageMapExtractIf<K, V, N> {
+        // the extracted entries came from this map, so they always fit in another one
+        let mut extracted = TinyMap::new();
+        self.rebuild(
+            |key, value| !f(key, value),
+            |key, value| {
+                extracted.insert(key, value);
+            },
+        );
+        StorageMapExtractIf(extracted.into_iter())
+    }
+
+    /// Tell whether this map contains a certain key.
+    #[inline]
+    pub fn contains_key(&self, key: &K) -> bool {
+        (self.0).0.contains_key(key)
+    }
+
+    /// Tell whether this map contains a certain key, given any borrowed form of it.
+    ///
+    /// When the `alloc` feature is disabled, this has the same O(n) cost as `get_borrowed`.
+    #[inline]
+    pub fn contains_key_borrowed<Q: ?Sized + StorageKey>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get_borrowed(key).is_some()
+    }
+
+    /// Tell whether any key in this map maps to `value`. Unlike `contains_key`, this has to
+    /// scan every value, so it takes O(n) time.
+    #[inline]
+    pub fn contains_value(&self, value: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        self.values().any(|v| v == value)
+    }
+
+    /// Get an iterator that iterates over the key-value pairs in arbitrary order.
+    #[allow(clippy::iter_without_into_iter)]
+    #[inline]
+    #[must_use]
+    pub fn iter(&self) -> StorageMapIter<'_, K, V, N> {
+        StorageMapIter::new((self.0).0.iter())
+    }
+
+    /// Get an iterator that iterates over the key-value pairs in arbitary order, mutably.
+    #[allow(clippy::iter_without_into_iter)]
+    #[inline]
+    pub fn iter_mut(&mut self) -> StorageMapIterMut<'_, K, V, N> {
+        StorageMapIterMut::new((self.0).0.iter_mut())
+    }
+
+    /// Get an iterator that iterates over the keys in arbitrary order.
+    #[inline]
+    #[must_use]
+    pub fn keys(&self) -> Keys<'_, K, V, N> {
+        Keys::new((self.0).0.keys())
+    }
+
+    /// Get an iterator that iterates over the values in arbitrary order.
+    #[inline]
+    #[must_use]
+    pub fn values(&self) -> Values<'_, K, V, N> {
+        Values::new((self.0).0.values())
+    }
+
+    /// Get an iterator that iterates over the values in arbitrary order, mutably.
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V, N> {
+        ValuesMut::new((self.0).0.values_mut())
+    }
+
+    /// Consume this map and apply `f` to each of its values, collecting the results into a new
+    /// map with the same keys. Since the number of entries is unchanged, this never overflows.
+    #[inline]
+    #[must_use]
+    pub fn map_values<W, F: FnMut(V) -> W>(self, mut f: F) -> StorageMap<K, W, N, S> {
+        let mut map = StorageMap::with_capacity(self.len());
+        for (key, value) in self {
+            map.insert(key, f(value));
+        }
+        map
+    }
+
+    /// Consume this map and get an iterator over its keys in arbitrary order.
+    #[inline]
+    #[must_use]
+    pub fn into_keys(self) -> IntoKeys<K, V, N> {
+        IntoKeys(self.into_iter())
+    }
+
+    /// Consume this map and get an iterator over its values in arbitrary order.
+    #[inline]
+    #[must_use]
+    pub fn into_values(self) -> IntoValues<K, V, N> {
+        IntoValues(self.into_iter())
+    }
+}
+
+// TinyMap's iterators can't be named, so when the alloc feature is disabled the borrowed items
+// are collected into an ArrayVec first. The map never holds more than N entries, so this can't
+// overflow.
+macro_rules! map_iterator {
+    ($(#[$meta: meta])* $name: ident, $inner: ident, $backend: ident, $item: ty) => {
+        $(#[$meta])*
+        #[repr(transparent)]
+        pub struct $name<'a, K, V, const N: usize>($inner<'a, K, V, N>);
+
+        #[cfg(feature = "alloc")]
+        #[repr(transparent)]
+        struct $inner<'a, K, V, const N: usize>(map::$backend<'a, K, V>, PhantomData<[(); N]>);
+
+        #[cfg(not(feature = "alloc"))]
+        #[repr(transparent)]
+        struct $inner<'a, K, V, const N: usize>(
+            ArrayVecIterator<[Option<$item>; N]>,
+            PhantomData<(&'a K, &'a V)>,
+        );
+
+        impl<'a, K, V, const N: usize> $name<'a, K, V, N> {
+            #[cfg(feature = "alloc")]
+            #[inline]
+            fn new(iter: map::$backend<'a, K, V>) -> Self {
+                Self($inner(iter, PhantomData))
+            }
+
+            #[cfg(not(feature = "alloc"))]
+            #[inline]
+            fn new<I: Iterator<Item = $item>>(iter: I) -> Self {
+                let items: ArrayVec<[Option<$item>; N]> = iter.map(Some).collect();
+                Self($inner(items.into_iter(), PhantomData))
+            }
+        }
+
+        impl<'a, K, V, const N: usize> Iterator for $name<'a, K, V, N> {
+            type Item = $item;
+
+            #[cfg(feature = "alloc")]
+            #[inline]
+            fn next(&mut self) -> Option<$item> {
+                (self.0).0.next()
+            }
+
+            #[cfg(not(feature = "alloc"))]
+            #[inline]
+            fn next(&mut self) -> Option<$item> {
+                (self.0).0.next().flatten()
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.0).0.size_hint()
+            }
+        }
+
+        impl<K, V, const N: usize> ExactSizeIterator for $name<'_, K, V, N> {}
+    };
+}
+
+map_iterator! {
+    /// An iterator over the key-value pairs of a `StorageMap`. Returned by `StorageMap::iter`.
+    StorageMapIter, SMIterImpl, Iter, (&'a K, &'a V)
+}
+
+map_iterator! {
+    /// A mutable iterator over the key-value pairs of a `StorageMap`. Returned by
+    /// `StorageMap::iter_mut`.
+    StorageMapIterMut, SMIterMutImpl, IterMut, (&'a K, &'a mut V)
+}
+
+map_iterator! {
+    /// An iterator over the keys of a `StorageMap`. Returned by `StorageMap::keys`.
+    Keys, SMKeysImpl, Keys, &'a K
+}
+
+map_iterator! {
+    /// An iterator over the values of a `StorageMap`. Returned by `StorageMap::values`.
+    Values, SMValuesImpl, Values, &'a V
+}
+
+map_iterator! {
+    /// A mutable iterator over the values of a `StorageMap`. Returned by
+    /// `StorageMap::values_mut`.
+    ValuesMut, SMValuesMutImpl, ValuesMut, &'a mut V
+}
+
+#[cfg(feature = "alloc")]
+type SMIntoIter<K, V, const N: usize> = map::IntoIter<K, V>;
+
+#[cfg(not(feature = "alloc"))]
+type SMIntoIter<K, V, const N: usize> = TinyMapIterator<K, V, N>;
+
+macro_rules! map_into_iterator {
+    ($(#[$meta: meta])* $name: ident, $item: ident, $field: tt) => {
+        $(#[$meta])*
+        #[repr(transparent)]
+        pub struct $name<K, V, const N: usize>(SMIntoIter<K, V, N>);
+
+        impl<K, V, const N: usize> Iterator for $name<K, V, N> {
+            type Item = $item;
+
+            #[inline]
+            fn next(&mut self) -> Option<$item> {
+                self.0.next().map(|entry| entry.$field)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+    };
+}
+
+map_into_iterator! {
+    /// An owning iterator over the keys of a `StorageMap`. Returned by `StorageMap::into_keys`.
+    IntoKeys, K, 0
+}
+
+map_into_iterator! {
+    /// An owning iterator over the values of a `StorageMap`. Returned by
+    /// `StorageMap::into_values`.
+    IntoValues, V, 1
+}
+
+#[cfg(feature = "alloc")]
+type SMExtractIter<K, V, const N: usize> = alloc::vec::IntoIter<(K, V)>;
+
+#[cfg(not(feature = "alloc"))]
+type SMExtractIter<K, V, const N: usize> = TinyMapIterator<K, V, N>;
+
+/// An iterator over the entries removed from a `StorageMap`. Returned by
+/// `StorageMap::extract_if`.
+#[repr(transparent)]
+pub struct StorageMapExtractIf<K, V, const N: usize>(SMExtractIter<K, V, N>);
+
+impl<K, V, const N: usize> Iterator for StorageMapExtractIf<K, V, N> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(K, V)> {
+        self.0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// A view into a single entry of a `StorageMap`, which may either be occupied or vacant.
+/// Returned by `StorageMap::entry`.
+///
+/// When the `alloc` feature is disabled, inserting through an entry requires `K: Clone`, since
+/// the key has to be looked up again once it has been moved into the map.
+#[repr(transparent)]
+pub struct Entry<'a, K: StorageKey, V, const N: usize, S = DefaultHashBuilder>(
+    EntryImpl<'a, K, V, N, S>,
+);
+
+#[cfg(all(feature = "alloc", not(feature = "ordered")))]
+#[repr(transparent)]
+struct EntryImpl<'a, K: StorageKey, V, const N: usize, S>(
+    map::Entry<'a, K, V, S>,
+    PhantomData<[V; N]>,
+);
+
+#[cfg(feature = "ordered")]
+#[repr(transparent)]
+struct EntryImpl<'a, K: StorageKey, V, const N: usize, S>(
+    map::Entry<'a, K, V>,
+    PhantomData<([V; N], S)>,
+);
+
+#[cfg(not(feature = "alloc"))]
+struct EntryImpl<'a, K: StorageKey, V, const N: usize, S> {
+    map: &'a mut TinyMap<K, V, N>,
+    key: K,
+    hasher: PhantomData<S>,
+}
+
+impl<K: StorageKey, V, const N: usize, S: BuildHasher> Entry<'_, K, V, N, S> {
+    /// Get the key this entry refers to.
+    #[inline]
+    #[must_use]
+    pub fn key(&self) -> &K {
+        self.key_impl()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn key_impl(&self) -> &K {
+        (self.0).0.key()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn key_impl(&self) -> &K {
+        &(self.0).key
+    }
+
+    /// If the entry is occupied, run `f` on the value it contains.
+    #[inline]
+    #[must_use]
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        self.and_modify_impl(f)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn and_modify_impl<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        Self(EntryImpl((self.0).0.and_modify(f), PhantomData))
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn and_modify_impl<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        if let Some(value) = (self.0).map.get_mut(&(self.0).key) {
+            f(value);
+        }
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, K: StorageKey, V, const N: usize, S: BuildHasher> Entry<'a, K, V, N, S> {
+    /// Try to insert `default` if the entry is vacant, and get a mutable reference to the
+    /// value in the entry.
+    ///
+    /// # Errors
+    ///
+    /// If the entry is vacant and the value cannot be inserted due to capacity overflow, the
+    /// key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_or_insert(self, default: V) -> Result<&'a mut V, (K, V)> {
+        Ok((self.0).0.or_insert(default))
+    }
+
+    /// Try to insert the result of `default` if the entry is vacant, and get a mutable
+    /// reference to the value in the entry.
+    ///
+    /// # Errors
+    ///
+    /// If the entry is vacant and the value cannot be inserted due to capacity overflow, the
+    /// key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_or_insert_with<F: FnOnce() -> V>(self, default: F) -> Result<&'a mut V, (K, V)> {
+        Ok((self.0).0.or_insert_with(default))
+    }
+
+    /// Insert `default` if the entry is vacant, and get a mutable reference to the value in
+    /// the entry.
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        (self.0).0.or_insert(default)
+    }
+
+    /// Insert the result of `default` if the entry is vacant, and get a mutable reference to
+    /// the value in the entry.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        (self.0).0.or_insert_with(default)
+    }
+
+    /// Try to insert the default value if the entry is vacant, and get a mutable reference to
+    /// the value in the entry.
+    ///
+    /// # Errors
+    ///
+    /// If the entry is vacant and the value cannot be inserted due to capacity overflow, the
+    /// key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_or_default(self) -> Result<&'a mut V, (K, V)>
+    where
+        V: Default,
+    {
+        self.try_or_insert_with(V::default)
+    }
+
+    /// Insert the default value if the entry is vacant, and get a mutable reference to the
+    /// value in the entry.
+    #[inline]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: StorageKey, V: Default, const N: usize, S: BuildHasher + Default> StorageMap<K, V, N, S> {
+    /// Try to get a mutable reference to the value for `key`, inserting the default value
+    /// first if the key is not in the map.
+    ///
+    /// # Errors
+    ///
+    /// If the key is not in the map and the value cannot be inserted due to capacity overflow,
+    /// the key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_get_or_insert_default(&mut self, key: K) -> Result<&mut V, (K, V)> {
+        self.entry(key).try_or_default()
+    }
+
+    /// Get a mutable reference to the value for `key`, inserting the default value first if
+    /// the key is not in the map.
+    #[inline]
+    pub fn get_or_insert_default(&mut self, key: K) -> &mut V {
+        self.entry(key).or_default()
+    }
+
+    /// Get a mutable reference to the counter for `key`, starting it at the default value if
+    /// the key is not in the map. This is the same as `get_or_insert_default`.
+    #[inline]
+    pub fn counter_entry(&mut self, key: K) -> &mut V {
+        self.get_or_insert_default(key)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl<'a, K: StorageKey + Clone, V, const N: usize, S: BuildHasher> Entry<'a, K, V, N, S> {
+    /// Try to insert `default` if the entry is vacant, and get a mutable reference to the
+    /// value in the entry.
+    ///
+    /// # Errors
+    ///
+    /// If the entry is vacant and the value cannot be inserted due to capacity overflow, the
+    /// key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_or_insert(self, default: V) -> Result<&'a mut V, (K, V)> {
+        self.try_or_insert_with(move || default)
+    }
+
+    /// Try to insert the result of `default` if the entry is vacant, and get a mutable
+    /// reference to the value in the entry.
+    ///
+    /// # Errors
+    ///
+    /// If the entry is vacant and the value cannot be inserted due to capacity overflow, the
+    /// key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_or_insert_with<F: FnOnce() -> V>(self, default: F) -> Result<&'a mut V, (K, V)> {
+        let EntryImpl { map, key, .. } = self.0;
+        if !map.contains_key(&key) {
+            map.try_insert(key.clone(), default())?;
+        }
+
+        match map.get_mut(&key) {
+            Some(value) => Ok(value),
+            None => unreachable!(),
+        }
+    }
+
+    /// Insert `default` if the entry is vacant, and get a mutable reference to the value in
+    /// the entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entry is vacant and the map is full.
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(move || default)
+    }
+
+    /// Insert the result of `default` if the entry is vacant, and get a mutable reference to
+    /// the value in the entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entry is vacant and the map is full.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self.try_or_insert_with(default) {
+            Ok(value) => value,
+            Err((_, _)) => {
+                panic!("<StorageMap> Failed to insert entry into map due to capacity overflow")
+            }
+        }
+    }
+
+    /// Try to insert the default value if the entry is vacant, and get a mutable reference to
+    /// the value in the entry.
+    ///
+    /// # Errors
+    ///
+    /// If the entry is vacant and the value cannot be inserted due to capacity overflow, the
+    /// key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_or_default(self) -> Result<&'a mut V, (K, V)>
+    where
+        V: Default,
+    {
+        self.try_or_insert_with(V::default)
+    }
+
+    /// Insert the default value if the entry is vacant, and get a mutable reference to the
+    /// value in the entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entry is vacant and the map is full.
+    #[inline]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl<K: StorageKey + Clone, V: Default, const N: usize, S: BuildHasher + Default>
+    StorageMap<K, V, N, S>
+{
+    /// Try to get a mutable reference to the value for `key`, inserting the default value
+    /// first if the key is not in the map.
+    ///
+    /// # Errors
+    ///
+    /// If the key is not in the map and the value cannot be inserted due to capacity overflow,
+    /// the key-value pair is returned back in an `Err`.
+    #[inline]
+    pub fn try_get_or_insert_default(&mut self, key: K) -> Result<&mut V, (K, V)> {
+        self.entry(key).try_or_default()
+    }
+
+    /// Get a mutable reference to the value for `key`, inserting the default value first if
+    /// the key is not in the map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not in the map and the map is full.
+    #[inline]
+    pub fn get_or_insert_default(&mut self, key: K) -> &mut V {
+        self.entry(key).or_default()
+    }
+
+    /// Get a mutable reference to the counter for `key`, starting it at the default value if
+    /// the key is not in the map. This is the same as `get_or_insert_default`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not in the map and the map is full.
+    #[inline]
+    pub fn counter_entry(&mut self, key: K) -> &mut V {
+        self.get_or_insert_default(key)
+    }
+}
+
+impl<K: StorageKey + fmt::Debug, V: fmt::Debug, const N: usize, S> fmt::Debug
+    for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&(self.0).0, f)
+    }
+}
+
+impl<K: StorageKey, V: PartialEq, const N: usize, S: BuildHasher + Default> PartialEq
+    for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: StorageKey, V: Eq, const N: usize, S: BuildHasher + Default> Eq for StorageMap<K, V, N, S> {}
+
+impl<K: StorageKey + Hash, V: Hash, const N: usize, S: BuildHasher + Default> Hash
+    for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // entries are hashed separately and summed, so that the result does not depend on
+        // the order the backing map iterates in
+        let sum = self.iter().fold(0u64, |sum, (k, v)| {
+            let mut hasher = FnvHasher::default();
+            k.hash(&mut hasher);
+            v.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        state.write_usize(self.len());
+        state.write_u64(sum);
+    }
+}
+
+/// # Panics
+///
+/// Panics if the key is not present in the map. Use `StorageMap::get` to handle this case
+/// instead.
+///
+impl<K: StorageKey, V, const N: usize, S: BuildHasher + Default> ops::Index<&K>
+    for StorageMap<K, V, N, S>
+{
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: &K) -> &V {
+        self.get(key)
+            .expect("<StorageMap> Key is not present in the map")
+    }
+}
+
+/// A 64-bit FNV-1a hasher.
+///
+/// `StorageMap` uses this to hash its entries in its `Hash` implementation, and to hash keys in
+/// `StorageMap::hash_key` for the backends that do not hash with `S`. When the `alloc` feature is
+/// disabled, `DefaultHashBuilder` builds this hasher, so it is public in order to be nameable.
+///
+/// The output is deterministic and does not depend on the backend, but FNV is not resistant to
+/// collision attacks, so it should not be used to hash untrusted input in a `HashMap`.
+#[derive(Debug, Clone)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    #[inline]
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+impl<K: StorageKey + Clone, V: Clone, const N: usize, S: Clone> Clone for SMImpl<K, V, N, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<K: StorageKey + Clone, V: Clone, const N: usize, S: Clone> Clone for StorageMap<K, V, N, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<K: StorageKey, V, const N: usize, S> iter::IntoIterator for StorageMap<K, V, N, S> {
+    type Item = (K, V);
+    #[cfg(feature = "alloc")]
+    type IntoIter = map::IntoIter<K, V>;
+    #[cfg(not(feature = "alloc"))]
+    type IntoIter = TinyMapIterator<K, V, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        (self.0).0.into_iter()
+    }
+}
+
+/// # Panics
+///
+/// If the `alloc` feature is disabled, this panics if the map runs out of space. Use
+/// `StorageMap::try_insert` to handle this case instead.
+impl<K: StorageKey, V, const N: usize, S: BuildHasher + Default> iter::Extend<(K, V)>
+    for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        // keys may already be in the map or be repeated, so only reserve the entire lower bound
+        // if the map is empty; otherwise, reserve half of it
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.reserve(if self.is_empty() {
+            lower
+        } else {
+            lower.div_ceil(2)
+        });
+
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// # Panics
+///
+/// If the `alloc` feature is disabled, this panics if the iterator yields more than `N` distinct
+/// keys.
+impl<K: StorageKey, V, const N: usize, S: BuildHasher + Default> iter::FromIterator<(K, V)>
+    for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: StorageKey, V, const N: usize, S: BuildHasher + Default> Default
+    for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: StorageKey + Serialize, V: Serialize, const N: usize, S: BuildHasher + Default> Serialize
+    for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+struct StorageMapVisitor<K, V, const N: usize, S>(PhantomData<([(K, V); N], S)>);
+
+#[cfg(feature = "serde")]
+impl<
+        'de,
+        K: StorageKey + Deserialize<'de>,
+        V: Deserialize<'de>,
+        const N: usize,
+        S: BuildHasher + Default,
+    > Visitor<'de> for StorageMapVisitor<K, V, N, S>
+{
+    type Value = StorageMap<K, V, N, S>;
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a map of at most {N} entries")
+    }
+
+    #[inline]
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = StorageMap::new();
+        while let Some((key, value)) = access.next_entry()? {
+            if let Err(_) = map.try_insert(key, value) {
+                return Err(de::Error::custom(
+                    "<StorageMap> Failed to insert entry into map due to capacity overflow",
+                ));
+            }
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<
+        'de,
+        K: StorageKey + Deserialize<'de>,
+        V: Deserialize<'de>,
+        const N: usize,
+        S: BuildHasher + Default,
+    > Deserialize<'de> for StorageMap<K, V, N, S>
+{
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(StorageMapVisitor(PhantomData))
+    }
+}
+
+// These tests run against whichever backend the enabled features select, so CI runs them once
+// for each feature combination.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map<const N: usize>(entries: &[(i32, i32)]) -> StorageMap<i32, i32, N> {
+        entries.iter().copied().collect()
+    }
+
+    #[test]
+    fn entry_or_insert_on_vacant_key() {
+        let mut map = map::<4>(&[(1, 10)]);
+        let entry = map.entry(2);
+        assert_eq!(*entry.key(), 2);
+        *entry.or_insert(20) += 1;
+        assert_eq!(map.get(&2), Some(&21));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn entry_or_insert_on_occupied_key() {
+        let mut map = map::<4>(&[(1, 10)]);
+        assert_eq!(*map.entry(1).or_insert(99), 10);
+        assert_eq!(*map.entry(1).or_insert_with(|| unreachable!()), 10);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn entry_and_modify() {
+        let mut map = map::<4>(&[(1, 10)]);
+        map.entry(1).and_modify(|v| *v += 1).or_insert(0);
+        map.entry(2).and_modify(|v| *v += 1).or_insert(0);
+        assert_eq!(map.get(&1), Some(&11));
+        assert_eq!(map.get(&2), Some(&0));
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn entry_on_full_map() {
+        let mut map = map::<2>(&[(1, 10), (2, 20)]);
+        assert_eq!(map.entry(3).try_or_insert(30), Err((3, 30)));
+        assert_eq!(map.entry(1).try_or_insert(30), Ok(&mut 10));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic(
+        expected = "<StorageMap> Failed to insert entry into map due to capacity overflow"
+    )]
+    fn entry_or_insert_on_full_map() {
+        let mut map = map::<2>(&[(1, 10), (2, 20)]);
+        map.entry(3).or_insert(30);
+    }
+
+    #[test]
+    fn clear_then_insert() {
+        let mut map = map::<4>(&[(1, 10), (2, 20)]);
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&1), None);
+        #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+        assert!(map.capacity() >= 2);
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(map.capacity(), 4);
+        map.insert(3, 30);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&3), Some(&30));
+    }
+
+    #[test]
+    fn retain_by_key_parity() {
+        let mut map = map::<8>(&[(1, 10), (2, 20), (3, 30), (4, 40)]);
+        map.retain(|k, v| {
+            *v += 1;
+            k % 2 == 0
+        });
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.get(&3), None);
+        assert_eq!(map.get(&2), Some(&21));
+        assert_eq!(map.get(&4), Some(&41));
+    }
+
+    #[test]
+    fn capacity_of_each_backend() {
+        let mut map: StorageMap<i32, i32, 4> = StorageMap::with_capacity(16);
+        #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+        {
+            assert!(map.capacity() >= 16);
+            map.reserve(32);
+            assert!(map.capacity() >= 32);
+        }
+        #[cfg(feature = "ordered")]
+        {
+            assert_eq!(map.capacity(), 0);
+            map.reserve(32);
+            assert_eq!(map.capacity(), 0);
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(map.capacity(), 4);
+            map.reserve(32);
+            assert_eq!(map.capacity(), 4);
+        }
+    }
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn storage_vec_as_key() {
+        let mut map: StorageMap<crate::StorageVec<i32, 4>, i32, 4> = StorageMap::new();
+        map.insert([1, 2].iter().copied().collect(), 12);
+        map.insert([3].iter().copied().collect(), 3);
+        let key: crate::StorageVec<i32, 4> = [1, 2].iter().copied().collect();
+        assert_eq!(map.get(&key), Some(&12));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn equal_maps_hash_equally() {
+        let a = map::<8>(&[(1, 10), (2, 20), (3, 30), (4, 40)]);
+        let b = map::<8>(&[(4, 40), (3, 30), (2, 20), (1, 10)]);
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_ne!(hash_of(&a), hash_of(&map::<8>(&[(1, 10)])));
+    }
+
+    #[test]
+    fn named_iterators_report_len() {
+        let mut map = map::<4>(&[(1, 10), (2, 20), (3, 30)]);
+        let iter: StorageMapIter<'_, i32, i32, 4> = map.iter();
+        assert_eq!(iter.len(), 3);
+        let mut keys: Keys<'_, i32, i32, 4> = map.keys();
+        keys.next();
+        assert_eq!(keys.len(), 2);
+        let values: Values<'_, i32, i32, 4> = map.values();
+        assert_eq!(values.len(), 3);
+        let values_mut: ValuesMut<'_, i32, i32, 4> = map.values_mut();
+        assert_eq!(values_mut.len(), 3);
+        let iter_mut: StorageMapIterMut<'_, i32, i32, 4> = map.iter_mut();
+        assert_eq!(iter_mut.len(), 3);
+    }
+
+    #[test]
+    fn remaining_capacity_reaches_zero() {
+        let mut map: StorageMap<i32, i32, 2> = StorageMap::new();
+        map.insert(1, 10);
+        map.insert(2, 20);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(map.remaining_capacity(), 0);
+            assert_eq!(map.try_insert(3, 30), Err((3, 30)));
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(map.remaining_capacity(), usize::MAX);
+            assert_eq!(map.try_insert(3, 30), Ok(None));
+        }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn insert_existing_key_into_full_map() {
+        let mut map = map::<2>(&[(1, 10), (2, 20)]);
+        assert_eq!(map.insert(1, 11), Some(10));
+        assert_eq!(map.get(&1), Some(&11));
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic(expected = "<StorageMap> capacity overflow")]
+    fn insert_into_full_map_panics() {
+        let mut map = map::<2>(&[(1, 10), (2, 20)]);
+        map.insert(3, 30);
+    }
+
+    #[test]
+    fn custom_deterministic_hasher() {
+        type Fnv = core::hash::BuildHasherDefault<FnvHasher>;
+
+        let mut a: StorageMap<i32, i32, 4, Fnv> = StorageMap::with_hasher(Fnv::default());
+        let mut b: StorageMap<i32, i32, 4, Fnv> =
+            StorageMap::with_capacity_and_hasher(4, Fnv::default());
+        a.insert(1, 10);
+        b.insert(1, 10);
+        assert_eq!(a.get(&1), Some(&10));
+        assert_eq!(a.hash_key(&1), b.hash_key(&1));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn get_many_mut_success_missing_and_duplicate() {
+        let mut map = map::<4>(&[(1, 10), (2, 20), (3, 30)]);
+        if let Some([a, b]) = map.get_many_mut([&1, &3]) {
+            core::mem::swap(a, b);
+        } else {
+            panic!("expected both keys to be found");
+        }
+        assert_eq!(map.get(&1), Some(&30));
+        assert_eq!(map.get(&3), Some(&10));
+        assert!(map.get_many_mut([&1, &4]).is_none());
+        assert!(map.get_many_mut([&2, &2]).is_none());
+    }
+
+    #[test]
+    fn borrowed_str_lookups() {
+        use std::string::{String, ToString};
+
+        let mut map: StorageMap<String, i32, 4> = StorageMap::new();
+        map.insert("one".to_string(), 1);
+        map.insert("two".to_string(), 2);
+        assert_eq!(map.get_borrowed("one"), Some(&1));
+        assert!(map.contains_key_borrowed("two"));
+        assert!(!map.contains_key_borrowed("three"));
+        *map.get_mut_borrowed("one").unwrap() += 10;
+        assert_eq!(
+            map.remove_entry_borrowed("one"),
+            Some(("one".to_string(), 11))
+        );
+        assert_eq!(map.remove_borrowed("three"), None);
+        assert_eq!(map.remove_borrowed("two"), Some(2));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn shrink_after_retain() {
+        let mut map: StorageMap<i32, i32, 4> = StorageMap::new();
+        let count = if cfg!(feature = "alloc") { 64 } else { 4 };
+        map.extend((0..count).map(|i| (i, i)));
+        map.retain(|k, _| *k < 2);
+        let before = map.capacity();
+        map.shrink_to(8);
+        map.shrink_to_fit();
+        assert_eq!(map.len(), 2);
+        #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+        assert!(map.capacity() < before && map.capacity() >= 2);
+        #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+        assert_eq!(map.capacity(), before);
+    }
+
+    #[test]
+    fn into_keys_and_into_values() {
+        let map = map::<4>(&[(1, 10), (2, 20), (3, 30)]);
+        let mut keys: std::vec::Vec<i32> = map.clone().into_keys().collect();
+        let mut values: std::vec::Vec<i32> = map.into_values().collect();
+        keys.sort_unstable();
+        values.sort_unstable();
+        assert_eq!(keys, [1, 2, 3]);
+        assert_eq!(values, [10, 20, 30]);
+    }
+
+    #[cfg(feature = "ordered")]
+    #[test]
+    fn ordered_iteration_is_sorted() {
+        let map = map::<4>(&[(3, 30), (1, 10), (4, 40), (2, 20)]);
+        assert!(map.keys().copied().eq(1..=4));
+        assert!(map
+            .iter()
+            .map(|(_, v)| *v)
+            .eq([10, 20, 30, 40].iter().copied()));
+    }
+
+    // hashbrown rehashes every key whenever it grows, so counting the hashes that are computed
+    // shows how often the map had to grow while being extended
+    #[cfg(all(feature = "alloc", not(feature = "ordered")))]
+    #[test]
+    fn extend_reserves_up_front() {
+        use core::{cell::Cell, hash::BuildHasherDefault};
+
+        std::thread_local! {
+            static HASHES: Cell<usize> = const { Cell::new(0) };
+        }
+
+        #[derive(Default)]
+        struct CountingHasher(FnvHasher);
+
+        impl Hasher for CountingHasher {
+            fn finish(&self) -> u64 {
+                HASHES.with(|hashes| hashes.set(hashes.get() + 1));
+                self.0.finish()
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.0.write(bytes);
+            }
+        }
+
+        type Counting = BuildHasherDefault<CountingHasher>;
+
+        for &count in &[10, 100, 1000, 10000] {
+            HASHES.with(|hashes| hashes.set(0));
+            let mut extended: StorageMap<i32, i32, 4, Counting> = StorageMap::new();
+            extended.extend((0..count).map(|i| (i, i)));
+            assert_eq!(HASHES.with(Cell::get), extended.len());
+
+            HASHES.with(|hashes| hashes.set(0));
+            let mut inserted: StorageMap<i32, i32, 4, Counting> = StorageMap::new();
+            (0..count).for_each(|i| {
+                inserted.insert(i, i);
+            });
+            assert!(HASHES.with(Cell::get) > inserted.len());
+        }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic(expected = "<StorageMap> capacity overflow")]
+    fn extend_past_capacity_panics() {
+        let mut map = map::<2>(&[(1, 10)]);
+        map.extend([(2, 20), (3, 30)].iter().copied());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn map_values_keeps_keys() {
+        use std::string::{String, ToString};
+
+        let map: StorageMap<i32, u32, 4> = [(1, 10), (2, 20)].iter().copied().collect();
+        let strings: StorageMap<i32, String, 4> = map.map_values(|v| v.to_string());
+        assert_eq!(strings.len(), 2);
+        assert_eq!(strings.get(&1).map(String::as_str), Some("10"));
+        assert_eq!(strings.get(&2).map(String::as_str), Some("20"));
+    }
+
+    #[test]
+    fn try_insert_within_capacity_stops_at_capacity() {
+        let mut map: StorageMap<i32, i32, 4> = StorageMap::with_capacity(4);
+        let capacity = map.capacity();
+        let mut key = 0;
+        while map.len() < capacity {
+            assert_eq!(map.try_insert_within_capacity(key, key), Ok(None));
+            key += 1;
+        }
+        assert_eq!(map.try_insert_within_capacity(-1, -1), Err((-1, -1)));
+        if let Some(&existing) = map.keys().next() {
+            assert_eq!(
+                map.try_insert_within_capacity(existing, 0),
+                Ok(Some(existing))
+            );
+        }
+        assert_eq!(map.capacity(), capacity);
+    }
+
+    #[test]
+    fn counter_entry_counts_repeated_keys() {
+        let mut map: StorageMap<i32, u32, 4> = StorageMap::new();
+        for &key in &[1, 2, 1, 3, 1, 2] {
+            *map.counter_entry(key) += 1;
+        }
+        assert_eq!(map.get(&1), Some(&3));
+        assert_eq!(map.get(&2), Some(&2));
+        assert_eq!(map.get(&3), Some(&1));
+        *map.entry(4).or_default() += 5;
+        assert_eq!(*map.get_or_insert_default(4), 5);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn try_get_or_insert_default_on_full_map() {
+        let mut map = map::<2>(&[(1, 10), (2, 20)]);
+        assert_eq!(map.try_get_or_insert_default(1), Ok(&mut 10));
+        assert_eq!(map.try_get_or_insert_default(3), Err((3, 0)));
+        assert_eq!(map.entry(3).try_or_default(), Err((3, 0)));
+    }
+
+    #[test]
+    fn extract_if_by_key_predicate() {
+        let mut map = map::<8>(&[(1, 10), (2, 20), (3, 30), (4, 40)]);
+        let mut extracted: std::vec::Vec<(i32, i32)> = map.extract_if(|k, _| k % 2 == 1).collect();
+        extracted.sort_unstable();
+        assert_eq!(extracted, [(1, 10), (3, 30)]);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&2), Some(&20));
+        assert_eq!(map.get(&4), Some(&40));
+        assert!(!map.contains_key(&1));
+        assert!(!map.contains_key(&3));
+    }
+
+    #[test]
+    fn contains_value_present_and_absent() {
+        let map = map::<4>(&[(1, 10), (2, 20)]);
+        assert!(map.contains_value(&20));
+        assert!(!map.contains_value(&30));
+        assert!(!map.contains_value(&1));
+    }
+
+    #[test]
+    fn insert_many_with_collects_rejects() {
+        let mut map = map::<2>(&[(1, 10)]);
+        let mut rejects = std::vec::Vec::new();
+        map.insert_many_with([(1, 11), (2, 20), (3, 30), (4, 40)], |k, v| {
+            rejects.push((k, v));
+        });
+        assert_eq!(map.get(&1), Some(&11));
+        assert_eq!(map.get(&2), Some(&20));
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(rejects, [(3, 30), (4, 40)]);
+            assert_eq!(map.len(), 2);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert!(rejects.is_empty());
+            assert_eq!(map.len(), 4);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn insert_many_past_n_rejects_nothing() {
+        let mut map = map::<2>(&[(1, 10)]);
+        assert!(map.insert_many([(2, 20), (3, 30)]).is_empty());
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn index_present_key() {
+        let map = map::<4>(&[(1, 10), (2, 20)]);
+        assert_eq!(map[&2], 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "<StorageMap> Key is not present in the map")]
+    fn index_absent_key_panics() {
+        let map = map::<4>(&[(1, 10)]);
+        let _ = map[&2];
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "ordered"))]
+    #[test]
+    fn ord_only_keys() {
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Key(i32);
+
+        let mut map: StorageMap<Key, i32, 4> = StorageMap::new();
+        map.insert(Key(2), 20);
+        map.insert(Key(1), 10);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&Key(1)), Some(&10));
+        assert_eq!(map.remove(&Key(2)), Some(20));
+        assert!(!map.contains_key(&Key(2)));
+    }
+
+    #[test]
+    fn is_full_after_filling_the_map() {
+        let mut map = map::<2>(&[(1, 10)]);
+        assert!(!map.is_full());
+        map.insert(2, 20);
+        #[cfg(not(feature = "alloc"))]
+        assert!(map.is_full());
+        #[cfg(feature = "alloc")]
+        assert!(!map.is_full());
+        map.remove(&1);
+        assert!(!map.is_full());
+    }
+
+    #[test]
+    fn hash_key_is_equal_for_equal_keys() {
+        let map = map::<4>(&[(1, 10)]);
+        assert_eq!(map.hash_key(&7), map.hash_key(&7));
+        assert_eq!(map.hash_key(&1), map.hash_key(&(2 - 1)));
+    }
+
+    #[test]
+    fn get_or_present_and_absent_keys() {
+        let map = map::<4>(&[(1, 10)]);
+        assert_eq!(*map.get_or(&1, &0), 10);
+        assert_eq!(*map.get_or(&2, &0), 0);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn remove_borrowed_panic_leaves_map_intact() {
+        #[derive(Debug, PartialOrd, Ord)]
+        struct Probe(i32);
+
+        impl PartialEq for Probe {
+            fn eq(&self, _: &Self) -> bool {
+                panic!("probe compared");
+            }
+        }
+
+        impl Eq for Probe {}
+
+        #[derive(Debug, PartialOrd, Ord)]
+        struct Key(Probe);
+
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                (self.0).0 == (other.0).0
+            }
+        }
+
+        impl Eq for Key {}
+
+        impl Borrow<Probe> for Key {
+            fn borrow(&self) -> &Probe {
+                &self.0
+            }
+        }
+
+        let mut map: StorageMap<Key, i32, 4> = StorageMap::new();
+        map.insert(Key(Probe(1)), 10);
+        map.insert(Key(Probe(2)), 20);
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            map.remove_borrowed(&Probe(2))
+        }));
+        assert!(result.is_err());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&Key(Probe(1))), Some(&10));
+        assert_eq!(map.get(&Key(Probe(2))), Some(&20));
+    }
+
+    #[test]
+    fn retain_panic_keeps_unvisited_entries() {
+        let mut map = map::<8>(&[(1, 10), (2, 20), (3, 30), (4, 40)]);
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            map.retain(|k, _| {
+                assert!(*k != 3, "predicate panicked");
+                *k != 1
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(map.get(&2), Some(&20));
+        assert_eq!(map.get(&3), Some(&30));
+        assert_eq!(map.get(&4), Some(&40));
+    }
+
+    #[test]
+    fn extract_if_panic_keeps_unvisited_entries() {
+        let mut map = map::<8>(&[(1, 10), (2, 20), (3, 30), (4, 40)]);
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            map.extract_if(|k, _| {
+                assert!(*k != 3, "predicate panicked");
+                *k == 1
+            })
+            .count()
+        }));
+        assert!(result.is_err());
+        assert_eq!(map.get(&2), Some(&20));
+        assert_eq!(map.get(&3), Some(&30));
+        assert_eq!(map.get(&4), Some(&40));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let map: StorageMap<i32, i32, 4> = [(1, 10), (2, 20)].iter().copied().collect();
+        let json = serde_json::to_string(&map).unwrap();
+        let back: StorageMap<i32, i32, 4> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, map);
+    }
+
+    #[test]
+    fn duplicate_keys_keep_the_last_value() {
+        let map: StorageMap<i32, i32, 4> = serde_json::from_str(r#"{"1":10,"1":20}"#).unwrap();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&1), Some(&20));
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn over_capacity_is_an_error() {
+        use std::string::ToString;
+
+        let err =
+            serde_json::from_str::<StorageMap<i32, i32, 2>>(r#"{"1":1,"2":2,"3":3}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("<StorageMap> Failed to insert entry into map due to capacity overflow"));
     }
 }
@@ -4,42 +4,96 @@
 //! storage depending on the `alloc` feature.
 
 #[cfg(not(feature = "alloc"))]
-use tinyvec::{ArrayVec, ArrayVecIterator};
+use tinyvec::{ArrayVec, ArrayVecDrain, ArrayVecIterator};
 
 #[cfg(all(feature = "alloc", not(feature = "stack")))]
-use alloc::vec::{self, Vec};
-#[cfg(all(feature = "alloc", not(feature = "stack")))]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::alloc::Layout;
+#[cfg(feature = "alloc")]
+use hashbrown::HashSet;
+
+#[cfg(any(not(feature = "alloc"), not(feature = "stack"), feature = "serde"))]
 use core::marker::PhantomData;
 
 #[cfg(all(feature = "alloc", feature = "stack"))]
-use tinyvec::{TinyVec, TinyVecIterator};
+use tinyvec::{TinyVec, TinyVecDrain, TinyVecIterator};
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "alloc"))]
+use core::ops::Bound;
+
+#[cfg(any(not(feature = "alloc"), feature = "stack"))]
+use core::mem;
+
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserialize, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, Serializer},
+};
 
 use core::{
-    fmt, iter,
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    iter,
     ops::{self, RangeBounds},
+    slice::{self, SliceIndex},
 };
 
+// Both variants of `StorageElement` share this definition, so that the documentation is only
+// written once.
+macro_rules! storage_element {
+    ($($bound: tt)*) => {
+        /// The bound placed on the elements of a `StorageVec`. The tinyvec backends need to fill
+        /// unused slots with default values, so this requires `Default` unless the `alloc`
+        /// feature is enabled without the `stack` feature. It is implemented for every type that
+        /// meets the bound.
+        ///
+        /// Since features are additive, libraries that want to work with any backend should
+        /// still require `Default` on their element types.
+        pub trait StorageElement: $($bound)* {}
+
+        impl<T: $($bound)*> StorageElement for T {}
+    };
+}
+
+#[cfg(any(not(feature = "alloc"), feature = "stack"))]
+storage_element!(Default);
+
+#[cfg(all(feature = "alloc", not(feature = "stack")))]
+storage_element!();
+
 /// A list-like object that will either use the tinyvec `ArrayVec`, the standard library `Vec`,
 /// or the tinyvec `TinyVec` as a backing implementation. It will use the `alloc` and `stack`
 /// features to control this.
 #[repr(transparent)]
 #[deprecated = "This crate is now deprecated."]
-pub struct StorageVec<T: Default, const N: usize>(SVImpl<T, N>);
+pub struct StorageVec<T: StorageElement, const N: usize>(SVImpl<T, N>);
 
 #[cfg(not(feature = "alloc"))]
 #[repr(transparent)]
-struct SVImpl<T: Default, const N: usize>(ArrayVec<[T; N]>);
+struct SVImpl<T: StorageElement, const N: usize>(ArrayVec<[T; N]>);
 
 #[cfg(all(feature = "alloc", not(feature = "stack")))]
 #[repr(transparent)]
-struct SVImpl<T: Default, const N: usize>(Vec<T>, PhantomData<[T; N]>);
+struct SVImpl<T: StorageElement, const N: usize>(Vec<T>, PhantomData<[T; N]>);
 
 #[cfg(all(feature = "alloc", feature = "stack"))]
 #[repr(transparent)]
-struct SVImpl<T: Default, const N: usize>(TinyVec<[T; N]>);
+struct SVImpl<T: StorageElement, const N: usize>(TinyVec<[T; N]>);
 
-impl<T: Default, const N: usize> StorageVec<T, N> {
+impl<T: StorageElement, const N: usize> StorageVec<T, N> {
     /// Create a new `StorageVec`.
+    ///
+    /// This is not a `const fn`, since the tinyvec constructors are not `const` and every
+    /// backend shares this signature.
     #[inline]
     #[must_use]
     pub fn new() -> Self {
@@ -64,262 +118,3328 @@ impl<T: Default, const N: usize> StorageVec<T, N> {
         Self(SVImpl(ArrayVec::new()))
     }
 
+    /// Create a new `StorageVec` with space for at least `cap` elements.
+    ///
+    /// When the `alloc` feature is disabled, the capacity is always `N` and `cap` is ignored.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        Self::with_capacity_impl(cap)
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
     #[inline]
-    fn deref_impl(&self) -> &[T] {
-        &(self.0).0
+    fn with_capacity_impl(cap: usize) -> Self {
+        Self(SVImpl(Vec::with_capacity(cap), PhantomData))
+    }
+
+    #[cfg(all(feature = "alloc", feature = "stack"))]
+    #[inline]
+    fn with_capacity_impl(cap: usize) -> Self {
+        Self(SVImpl(TinyVec::with_capacity(cap)))
     }
 
+    #[cfg(not(feature = "alloc"))]
     #[inline]
-    fn deref_mut_impl(&mut self) -> &mut [T] {
-        &mut (self.0).0
+    fn with_capacity_impl(_cap: usize) -> Self {
+        Self::new()
     }
 
-    /// Try to push an item onto this list.
+    /// Try to create a new `StorageVec` with space for at least `cap` elements.
     ///
     /// # Errors
     ///
-    /// If the push operation fails due to capacity overflow, the element is returned back
-    /// in an `Err`.
+    /// If the `alloc` feature is disabled and `cap` is greater than `N`, an `Err` is returned.
+    /// This never fails when the `alloc` feature is enabled.
+    #[allow(clippy::result_unit_err)]
     #[inline]
-    pub fn try_push(&mut self, item: T) -> Result<(), T> {
-        self.try_push_impl(item)
+    pub fn try_with_capacity(cap: usize) -> Result<Self, ()> {
+        Self::try_with_capacity_impl(cap)
     }
 
     #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_wraps)]
     #[inline]
-    fn try_push_impl(&mut self, item: T) -> Result<(), T> {
-        (self.0).0.push(item);
-        Ok(())
+    fn try_with_capacity_impl(cap: usize) -> Result<Self, ()> {
+        Ok(Self::with_capacity(cap))
     }
 
     #[cfg(not(feature = "alloc"))]
     #[inline]
-    fn try_push_impl(&mut self, item: T) -> Result<(), T> {
-        match (self.0).0.try_push(item) {
-            None => Ok(()),
-            Some(reject) => Err(reject),
+    fn try_with_capacity_impl(cap: usize) -> Result<Self, ()> {
+        if cap > N {
+            Err(())
+        } else {
+            Ok(Self::new())
         }
     }
 
-    /// Push an item onto this list, and panic if the push operation failed.
+    /// Create a new `StorageVec` containing every element of `array`.
     #[inline]
-    pub fn push(&mut self, item: T) {
-        if let Err(_) = self.try_push(item) {
-            panic!("<StorageVec> Failed to push item onto list due to capacity overflow");
-        }
+    #[must_use]
+    pub fn from_array(array: [T; N]) -> Self {
+        Self::from_array_impl(array)
     }
 
-    /// Pop an item from the back of this list.
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
     #[inline]
-    pub fn pop(&mut self) -> Option<T> {
-        self.pop_impl()
+    fn from_array_impl(array: [T; N]) -> Self {
+        Self(SVImpl(Vec::from(array), PhantomData))
     }
 
-    #[cfg(feature = "alloc")]
+    #[cfg(all(feature = "alloc", feature = "stack"))]
     #[inline]
-    fn pop_impl(&mut self) -> Option<T> {
-        (self.0).0.pop()
+    fn from_array_impl(array: [T; N]) -> Self {
+        Self(SVImpl(TinyVec::from(array)))
     }
 
     #[cfg(not(feature = "alloc"))]
     #[inline]
-    fn pop_impl(&mut self) -> Option<T> {
-        match (self.0).0.pop() {
-            Some(p) => Some(p),
-            None => None,
-        }
+    fn from_array_impl(array: [T; N]) -> Self {
+        Self(SVImpl(ArrayVec::from(array)))
     }
 
-    /// Try to insert an item into this list.
+    /// Convert this list back into an array. This only succeeds if the list holds exactly `N`
+    /// elements.
     ///
     /// # Errors
     ///
-    /// If the element cannot be inserted into the list due to capacity overflow,
-    /// the element is returned back in an `Err`.
+    /// If the length of this list is not `N`, the list is returned back unchanged in an `Err`.
     #[inline]
-    pub fn try_insert(&mut self, item: T, index: usize) -> Result<(), T> {
-        self.try_insert_impl(item, index)
+    pub fn into_array(self) -> Result<[T; N], Self> {
+        self.into_array_impl()
     }
 
-    #[cfg(feature = "alloc")]
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
     #[inline]
-    fn try_insert_impl(&mut self, item: T, index: usize) -> Result<(), T> {
-        (self.0).0.insert(index, item);
-        Ok(())
+    fn into_array_impl(self) -> Result<[T; N], Self> {
+        <[T; N]>::try_from((self.0).0).map_err(|list| Self(SVImpl(list, PhantomData)))
+    }
+
+    #[cfg(all(feature = "alloc", feature = "stack"))]
+    #[inline]
+    fn into_array_impl(self) -> Result<[T; N], Self> {
+        match (self.0).0 {
+            TinyVec::Inline(list) if list.len() == N => Ok(list.into_inner()),
+            TinyVec::Heap(list) => {
+                <[T; N]>::try_from(list).map_err(|list| Self(SVImpl(TinyVec::Heap(list))))
+            }
+            list @ TinyVec::Inline(_) => Err(Self(SVImpl(list))),
+        }
     }
 
     #[cfg(not(feature = "alloc"))]
     #[inline]
-    fn try_insert_impl(&mut self, item: T, index: usize) -> Result<(), T> {
-        match (self.0).0.try_insert(index, item) {
-            None => Ok(()),
-            Some(reject) => Err(reject),
+    fn into_array_impl(self) -> Result<[T; N], Self> {
+        if self.len() == N {
+            Ok((self.0).0.into_inner())
+        } else {
+            Err(self)
         }
     }
 
-    /// Insert an item into this list, and panic if the insert operation fails.
+    /// Try to create a new `StorageVec` from the items of an iterator. Unlike the
+    /// `FromIterator` implementation, this does not panic if the iterator yields more items
+    /// than the list can hold.
+    ///
+    /// # Errors
+    ///
+    /// If an item cannot be pushed onto the list due to capacity overflow, that item is
+    /// returned back in an `Err` and the rest of the iterator is left unconsumed.
     #[inline]
-    pub fn insert(&mut self, item: T, index: usize) {
-        if let Err(_) = self.try_insert(item, index) {
-            panic!("<StorageVec> Failed to insert item into list due to capacity overflow");
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, T> {
+        let mut collection = Self::new();
+        for item in iter {
+            collection.try_push(item)?;
         }
+        Ok(collection)
     }
 
-    /// Remove an item from this list.
+    /// Get the number of elements in this list.
     #[inline]
-    pub fn remove(&mut self, index: usize) -> Option<T> {
-        if index >= self.len() - 1 {
-            None
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.deref_impl().len()
+    }
+
+    /// Tell whether or not this list is empty.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.deref_impl().is_empty()
+    }
+
+    /// Get the number of elements this list can hold. When the `alloc` feature is disabled,
+    /// this is always `N`; otherwise, it is the number of elements that can be held without
+    /// reallocating.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        (self.0).0.capacity()
+    }
+
+    /// Tell whether the elements of this list are currently stored inline, rather than on the
+    /// heap. This is always `true` when the `alloc` feature is disabled, and always `false`
+    /// when the `alloc` feature is enabled without the `stack` feature. With both features,
+    /// this becomes `false` once the list has spilled past `N` elements.
+    #[inline]
+    #[must_use]
+    pub fn is_inline(&self) -> bool {
+        self.is_inline_impl()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn is_inline_impl(&self) -> bool {
+        true
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn is_inline_impl(&self) -> bool {
+        false
+    }
+
+    #[cfg(all(feature = "alloc", feature = "stack"))]
+    #[inline]
+    fn is_inline_impl(&self) -> bool {
+        (self.0).0.is_inline()
+    }
+
+    /// Try to reserve capacity for at least `additional` more elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TryReserveError::AllocError` if the allocation fails. Returns
+    /// `TryReserveError::CapacityOverflow` if the new capacity would overflow, or if the `alloc`
+    /// feature is disabled and there is not enough room left in the list for `additional` more
+    /// elements.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_reserve_impl(additional)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_reserve_impl(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if additional <= N - self.len() {
+            Ok(())
         } else {
-            Some(self.remove_impl(index))
+            Err(TryReserveError::CapacityOverflow)
+        }
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    #[inline]
+    fn try_reserve_impl(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let list = &mut (self.0).0;
+        list.try_reserve(additional)
+            .map_err(|_| TryReserveError::classify::<T>(list.len(), additional))
+    }
+
+    #[cfg(all(feature = "alloc", feature = "stack"))]
+    #[inline]
+    fn try_reserve_impl(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        match &mut (self.0).0 {
+            TinyVec::Heap(list) => list
+                .try_reserve(additional)
+                .map_err(|_| TryReserveError::classify::<T>(list.len(), additional)),
+            TinyVec::Inline(list) => {
+                if additional <= N - list.len() {
+                    return Ok(());
+                }
+
+                let mut heap = Vec::new();
+                heap.try_reserve(
+                    list.len()
+                        .checked_add(additional)
+                        .ok_or(TryReserveError::CapacityOverflow)?,
+                )
+                .map_err(|_| TryReserveError::classify::<T>(list.len(), additional))?;
+                heap.extend(list.drain(..));
+                (self.0).0 = TinyVec::Heap(heap);
+                Ok(())
+            }
         }
     }
 
+    /// Shrink the capacity of this list as much as possible. With both the `alloc` and `stack`
+    /// features, this moves the elements back inline if they fit. When the `alloc` feature is
+    /// disabled, this does nothing.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit_impl();
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn shrink_to_fit_impl(&mut self) {}
+
     #[cfg(feature = "alloc")]
     #[inline]
-    fn remove_impl(&mut self, index: usize) -> T {
-        (self.0).0.remove(index)
+    fn shrink_to_fit_impl(&mut self) {
+        (self.0).0.shrink_to_fit();
+    }
+
+    /// Shrink the capacity of this list, keeping it at least as large as both its length and
+    /// `min_capacity`. When the `alloc` feature is disabled, this does nothing.
+    #[inline]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.shrink_to_impl(min_capacity);
     }
 
     #[cfg(not(feature = "alloc"))]
+    #[allow(clippy::unused_self)]
+    #[inline]
+    fn shrink_to_impl(&mut self, _min_capacity: usize) {}
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
     #[inline]
-    fn remove_impl(&mut self, index: usize) -> T {
-        (self.0).0.remove(index)
+    fn shrink_to_impl(&mut self, min_capacity: usize) {
+        (self.0).0.shrink_to(min_capacity);
     }
 
-    /// Create a drain iterator for this vector.
+    #[cfg(all(feature = "alloc", feature = "stack"))]
     #[inline]
-    pub fn drain<'a, R: RangeBounds<usize> + 'a>(
-        &'a mut self,
-        range: R,
-    ) -> impl Iterator<Item = T> + 'a
-    where
-        T: 'a,
-    {
-        (self.0).0.drain(range)
+    fn shrink_to_impl(&mut self, min_capacity: usize) {
+        match &mut (self.0).0 {
+            TinyVec::Heap(list) if min_capacity > N || list.len() > N => {
+                list.shrink_to(min_capacity);
+            }
+            list => list.shrink_to_fit(),
+        }
     }
-}
 
-/// An owning iterator for the `StorageVec`. Returned by `StorageVec::into_iter`.
-#[repr(transparent)]
-pub struct StorageVecIterator<T: Default, const N: usize>(SVIterImpl<T, N>);
+    /// Get a slice containing every element of this list.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        self.deref_impl()
+    }
 
-#[cfg(not(feature = "alloc"))]
-#[repr(transparent)]
-struct SVIterImpl<T: Default, const N: usize>(ArrayVecIterator<[T; N]>);
+    /// Get a mutable slice containing every element of this list.
+    #[inline]
+    #[must_use]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.deref_mut_impl()
+    }
 
-#[cfg(all(feature = "alloc", not(feature = "stack")))]
-#[repr(transparent)]
-struct SVIterImpl<T: Default, const N: usize>(vec::IntoIter<T>, PhantomData<[(); N]>);
+    /// Get a raw pointer to the first element of this list, for passing the buffer across FFI.
+    ///
+    /// Calling this is safe, but dereferencing the pointer is not. The pointer is only valid
+    /// for reads of `len` elements, and only until the list is modified or moved. When the
+    /// elements are stored inline, moving the list moves the elements along with it.
+    #[inline]
+    #[must_use]
+    pub fn as_ptr(&self) -> *const T {
+        self.deref_impl().as_ptr()
+    }
 
-#[cfg(all(feature = "alloc", feature = "stack"))]
-#[repr(transparent)]
-struct SVIterImpl<T: Default, const N: usize>(TinyVecIterator<[T; N]>);
+    /// Get a raw mutable pointer to the first element of this list, for passing the buffer
+    /// across FFI.
+    ///
+    /// Calling this is safe, but dereferencing the pointer is not. The pointer is only valid
+    /// for reads and writes of `len` elements, and only until the list is otherwise modified or
+    /// moved. When the elements are stored inline, moving the list moves the elements along
+    /// with it.
+    #[inline]
+    #[must_use]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.deref_mut_impl().as_mut_ptr()
+    }
 
-impl<T: Default, const N: usize> StorageVecIterator<T, N> {
-    #[cfg(any(not(feature = "alloc"), feature = "stack"))]
+    /// Get an iterator over mutable references to the elements of this list.
     #[inline]
-    fn new(list: StorageVec<T, N>) -> Self {
-        Self(SVIterImpl((list.0).0.into_iter()))
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.deref_mut_impl().iter_mut()
     }
 
-    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    /// Get an iterator over the elements of this list, from back to front.
     #[inline]
-    fn new(list: StorageVec<T, N>) -> Self {
-        Self(SVIterImpl((list.0).0.into_iter(), PhantomData))
+    pub fn rev_iter(&self) -> iter::Rev<slice::Iter<'_, T>> {
+        self.deref_impl().iter().rev()
     }
-}
 
-impl<T: Default, const N: usize> Iterator for StorageVecIterator<T, N> {
-    type Item = T;
+    /// Get an iterator over mutable references to the elements of this list, from back to front.
+    #[inline]
+    pub fn rev_iter_mut(&mut self) -> iter::Rev<slice::IterMut<'_, T>> {
+        self.deref_mut_impl().iter_mut().rev()
+    }
 
+    /// Get an iterator over the elements of this list, paired with their indices. This is the
+    /// same as `iter().enumerate()`.
     #[inline]
-    fn next(&mut self) -> Option<T> {
-        (self.0).0.next()
+    pub fn iter_indexed(&self) -> iter::Enumerate<slice::Iter<'_, T>> {
+        self.deref_impl().iter().enumerate()
     }
 
+    /// Get an iterator over mutable references to the elements of this list, paired with their
+    /// indices. This is the same as `iter_mut().enumerate()`.
     #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.0).0.size_hint()
+    pub fn iter_indexed_mut(&mut self) -> iter::Enumerate<slice::IterMut<'_, T>> {
+        self.deref_mut_impl().iter_mut().enumerate()
     }
-}
 
-impl<T: Default, const N: usize> ExactSizeIterator for StorageVecIterator<T, N> {}
+    /// Tell whether this list contains an element equal to `item`.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, item: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.deref_impl().contains(item)
+    }
 
-#[cfg(not(feature = "stack"))]
-impl<T: Default, const N: usize> DoubleEndedIterator for StorageVecIterator<T, N> {
+    /// Tell whether `needle` is a prefix of this list.
     #[inline]
-    fn next_back(&mut self) -> Option<T> {
-        (self.0).0.next_back()
+    #[must_use]
+    pub fn starts_with(&self, needle: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        self.deref_impl().starts_with(needle)
     }
-}
 
-impl<T: Default, const N: usize> ops::Deref for StorageVec<T, N> {
-    type Target = [T];
+    /// Tell whether `needle` is a suffix of this list.
+    #[inline]
+    #[must_use]
+    pub fn ends_with(&self, needle: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        self.deref_impl().ends_with(needle)
+    }
 
+    /// Get the index of the first element that satisfies the predicate `f`.
     #[inline]
-    fn deref(&self) -> &[T] {
-        self.deref_impl()
+    pub fn position<F: FnMut(&T) -> bool>(&self, f: F) -> Option<usize> {
+        self.deref_impl().iter().position(f)
     }
-}
 
-impl<T: Clone + Default, const N: usize> Clone for StorageVec<T, N> {
-    #[cfg(any(not(feature = "alloc"), feature = "stack"))]
+    /// Get a reference to the first element that satisfies the predicate `f`.
     #[inline]
-    fn clone(&self) -> Self {
-        Self(SVImpl((self.0).0.clone()))
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut f: F) -> Option<&T> {
+        self.deref_impl().iter().find(|item| f(item))
     }
 
-    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    /// Get a reference to an element or a subslice of this list, or `None` if `index` is out of
+    /// bounds. This accepts either a single index or a range of indices.
     #[inline]
-    fn clone(&self) -> Self {
-        Self(SVImpl((self.0).0.clone(), PhantomData))
+    #[must_use]
+    pub fn get<I: SliceIndex<[T]>>(&self, index: I) -> Option<&I::Output> {
+        self.deref_impl().get(index)
     }
-}
 
-impl<T: Default, const N: usize> ops::DerefMut for StorageVec<T, N> {
+    /// Get a mutable reference to an element or a subslice of this list, or `None` if `index`
+    /// is out of bounds. This accepts either a single index or a range of indices.
     #[inline]
-    fn deref_mut(&mut self) -> &mut [T] {
-        self.deref_mut_impl()
+    #[must_use]
+    pub fn get_mut<I: SliceIndex<[T]>>(&mut self, index: I) -> Option<&mut I::Output> {
+        self.deref_mut_impl().get_mut(index)
     }
-}
 
-impl<T: Default, const N: usize> iter::IntoIterator for StorageVec<T, N> {
-    type Item = T;
-    type IntoIter = StorageVecIterator<T, N>;
+    /// Get a reference to the element at `index`, or `default` if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn get_or<'a>(&'a self, index: usize, default: &'a T) -> &'a T {
+        self.deref_impl().get(index).unwrap_or(default)
+    }
 
+    /// Get a reference to the first element of this list, or `None` if it is empty.
     #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        StorageVecIterator::new(self)
+    #[must_use]
+    pub fn first(&self) -> Option<&T> {
+        self.deref_impl().first()
     }
-}
 
-impl<T: Default, const N: usize> iter::Extend<T> for StorageVec<T, N> {
+    /// Get a mutable reference to the first element of this list, or `None` if it is empty.
     #[inline]
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        (self.0).0.extend(iter)
+    #[must_use]
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.deref_mut_impl().first_mut()
     }
-}
 
-impl<T: Default, const N: usize> iter::FromIterator<T> for StorageVec<T, N> {
+    /// Get a reference to the last element of this list, or `None` if it is empty.
     #[inline]
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut collection = Self::new();
-        collection.extend(iter);
-        collection
+    #[must_use]
+    pub fn last(&self) -> Option<&T> {
+        self.deref_impl().last()
     }
-}
 
-impl<T: Default, const N: usize> Default for StorageVec<T, N> {
+    /// Get a mutable reference to the last element of this list, or `None` if it is empty.
     #[inline]
-    fn default() -> Self {
-        Self::new()
+    #[must_use]
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.deref_mut_impl().last_mut()
     }
-}
 
-impl<T: Default + fmt::Debug, const N: usize> fmt::Debug for StorageVec<T, N> {
+    /// Split this list into its first element and the rest of the elements, or `None` if it is
+    /// empty.
     #[inline]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&(self.0).0, f)
+    #[must_use]
+    pub fn split_first(&self) -> Option<(&T, &[T])> {
+        self.deref_impl().split_first()
+    }
+
+    /// Split this list into a mutable reference to its first element and the rest of the
+    /// elements, or `None` if it is empty.
+    #[inline]
+    #[must_use]
+    pub fn split_first_mut(&mut self) -> Option<(&mut T, &mut [T])> {
+        self.deref_mut_impl().split_first_mut()
+    }
+
+    /// Split this list into its last element and the rest of the elements, or `None` if it is
+    /// empty.
+    #[inline]
+    #[must_use]
+    pub fn split_last(&self) -> Option<(&T, &[T])> {
+        self.deref_impl().split_last()
+    }
+
+    /// Split this list into a mutable reference to its last element and the rest of the
+    /// elements, or `None` if it is empty.
+    #[inline]
+    #[must_use]
+    pub fn split_last_mut(&mut self) -> Option<(&mut T, &mut [T])> {
+        self.deref_mut_impl().split_last_mut()
+    }
+
+    /// Split this list into a slice of the leading elements and a reference to an array of the
+    /// last `M` elements, or `None` if it has fewer than `M` elements.
+    #[inline]
+    #[must_use]
+    pub fn split_last_chunk<const M: usize>(&self) -> Option<(&[T], &[T; M])> {
+        let mid = self.len().checked_sub(M)?;
+        let (init, last) = self.deref_impl().split_at(mid);
+        <&[T; M]>::try_from(last).ok().map(|last| (init, last))
+    }
+
+    /// Split this list into two slices at `mid`. The first slice contains the elements in
+    /// `[0, mid)`, and the second contains the elements in `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[inline]
+    #[must_use]
+    pub fn split_at(&self, mid: usize) -> (&[T], &[T]) {
+        self.deref_impl().split_at(mid)
+    }
+
+    /// Split this list into two mutable slices at `mid`. The first slice contains the elements
+    /// in `[0, mid)`, and the second contains the elements in `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[inline]
+    #[must_use]
+    pub fn split_at_mut(&mut self, mid: usize) -> (&mut [T], &mut [T]) {
+        self.deref_mut_impl().split_at_mut(mid)
+    }
+
+    /// Sort this list. This sort is stable.
+    ///
+    /// Stable sorting requires an allocator, so this is only available with the `alloc`
+    /// feature. Use `sort_unstable` otherwise.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.deref_mut_impl().sort();
+    }
+
+    /// Sort this list using the comparator `f`. This sort is stable.
+    ///
+    /// Stable sorting requires an allocator, so this is only available with the `alloc`
+    /// feature. Use `sort_unstable_by` otherwise.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, f: F) {
+        self.deref_mut_impl().sort_by(f);
+    }
+
+    /// Sort this list by the key returned by `f`. This sort is stable.
+    ///
+    /// Stable sorting requires an allocator, so this is only available with the `alloc`
+    /// feature.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        self.deref_mut_impl().sort_by_key(f);
+    }
+
+    /// Sort this list without preserving the order of equal elements.
+    #[inline]
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        self.deref_mut_impl().sort_unstable();
+    }
+
+    /// Sort this list using the comparator `f`, without preserving the order of equal
+    /// elements.
+    #[inline]
+    pub fn sort_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, f: F) {
+        self.deref_mut_impl().sort_unstable_by(f);
+    }
+
+    /// Swap the elements at indices `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `a` or `b` is out of bounds.
+    #[inline]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.deref_mut_impl().swap(a, b);
+    }
+
+    /// Reverse the order of the elements in this list.
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.deref_mut_impl().reverse();
+    }
+
+    /// Rotate this list in place so that the element at `mid` becomes the first element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the length of this list.
+    #[inline]
+    pub fn rotate_left(&mut self, mid: usize) {
+        self.deref_mut_impl().rotate_left(mid);
+    }
+
+    /// Rotate this list in place so that the last `k` elements move to the front.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is greater than the length of this list.
+    #[inline]
+    pub fn rotate_right(&mut self, k: usize) {
+        self.deref_mut_impl().rotate_right(k);
+    }
+
+    /// Overwrite every element of this list with clones of `value`. The length is unchanged.
+    #[inline]
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.deref_mut_impl().fill(value);
+    }
+
+    /// Overwrite every element of this list with values returned by `f`. The length is
+    /// unchanged.
+    #[inline]
+    pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
+        self.deref_mut_impl().fill_with(f);
+    }
+
+    /// Binary search this sorted list for `x`, returning its index in an `Ok` if it is found.
+    ///
+    /// # Errors
+    ///
+    /// If `x` is not found, the index where it could be inserted while keeping the list sorted
+    /// is returned in an `Err`.
+    #[inline]
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.deref_impl().binary_search(x)
+    }
+
+    /// Binary search this sorted list using the comparator `f`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the insertion point if no matching element is found.
+    #[inline]
+    pub fn binary_search_by<F: FnMut(&T) -> Ordering>(&self, f: F) -> Result<usize, usize> {
+        self.deref_impl().binary_search_by(f)
+    }
+
+    /// Binary search this list, sorted by the key returned by `f`, for the key `b`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the insertion point if no matching element is found.
+    #[inline]
+    pub fn binary_search_by_key<B: Ord, F: FnMut(&T) -> B>(
+        &self,
+        b: &B,
+        f: F,
+    ) -> Result<usize, usize> {
+        self.deref_impl().binary_search_by_key(b, f)
+    }
+
+    /// Get an iterator over non-overlapping chunks of `size` elements. The last chunk may be
+    /// shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    #[inline]
+    pub fn chunks(&self, size: usize) -> slice::Chunks<'_, T> {
+        self.deref_impl().chunks(size)
+    }
+
+    /// Get an iterator over every contiguous window of `size` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    #[inline]
+    pub fn windows(&self, size: usize) -> slice::Windows<'_, T> {
+        self.deref_impl().windows(size)
+    }
+
+    /// Get an iterator over the sub-slices separated by elements that satisfy `pred`. The
+    /// separating elements are not included in the sub-slices.
+    #[inline]
+    pub fn split<F: FnMut(&T) -> bool>(&self, pred: F) -> slice::Split<'_, T, F> {
+        self.deref_impl().split(pred)
+    }
+
+    /// Get an iterator over the sub-slices separated by elements that satisfy `pred`, starting
+    /// from the back of this list. The separating elements are not included in the sub-slices.
+    #[inline]
+    pub fn rsplit<F: FnMut(&T) -> bool>(&self, pred: F) -> slice::RSplit<'_, T, F> {
+        self.deref_impl().rsplit(pred)
+    }
+
+    #[inline]
+    fn deref_impl(&self) -> &[T] {
+        &(self.0).0
+    }
+
+    #[inline]
+    fn deref_mut_impl(&mut self) -> &mut [T] {
+        &mut (self.0).0
+    }
+
+    /// Try to push an item onto this list.
+    ///
+    /// # Errors
+    ///
+    /// If the push operation fails due to capacity overflow, the element is returned back
+    /// in an `Err`.
+    #[inline]
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        self.try_push_impl(item)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_push_impl(&mut self, item: T) -> Result<(), T> {
+        (self.0).0.push(item);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_push_impl(&mut self, item: T) -> Result<(), T> {
+        match (self.0).0.try_push(item) {
+            None => Ok(()),
+            Some(reject) => Err(reject),
+        }
+    }
+
+    /// Push an item onto this list, but only if it fits within the current capacity. This never
+    /// reallocates. When the `alloc` feature is disabled, this is the same as `try_push`.
+    ///
+    /// # Errors
+    ///
+    /// If the list is already at capacity, the element is returned back in an `Err`.
+    #[inline]
+    pub fn push_within_capacity(&mut self, item: T) -> Result<(), T> {
+        if self.len() < self.capacity() {
+            self.try_push(item)
+        } else {
+            Err(item)
+        }
+    }
+
+    /// Push an item onto this list, and panic if the push operation failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and the list already holds `N` elements. The
+    /// panic message includes the length and capacity, which helps to spot an `N` that is too
+    /// small. Use `try_push` to handle this case instead. Debug builds check this before
+    /// trying to push, and also suggest raising `N`.
+    #[inline]
+    pub fn push(&mut self, item: T) {
+        #[cfg(not(feature = "alloc"))]
+        debug_assert!(
+            self.len() < N,
+            "<StorageVec> Pushed onto a full list, capacity overflow: len {} == capacity {}; consider a larger N",
+            self.len(),
+            N
+        );
+
+        // a failed push hands the item back and leaves the list unchanged
+        if let Err(_) = self.try_push(item) {
+            panic!(
+                "<StorageVec> Failed to push item onto list due to capacity overflow: len {} == capacity {}",
+                self.len(),
+                self.capacity()
+            );
+        }
+    }
+
+    /// Pop an item from the back of this list.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.pop_impl()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn pop_impl(&mut self) -> Option<T> {
+        (self.0).0.pop()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn pop_impl(&mut self) -> Option<T> {
+        match (self.0).0.pop() {
+            Some(p) => Some(p),
+            None => None,
+        }
+    }
+
+    /// Pop the last item from this list, but only if `predicate` returns `true` for it. Returns
+    /// `None` if the list is empty or the predicate returns `false`.
+    #[inline]
+    pub fn pop_if<F: FnOnce(&T) -> bool>(&mut self, predicate: F) -> Option<T> {
+        if predicate(self.last()?) {
+            self.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Remove every item from this list. Any heap capacity is retained.
+    #[inline]
+    pub fn clear(&mut self) {
+        (self.0).0.clear();
+    }
+
+    /// Try to insert an item into this list at the given index, shifting all elements after it
+    /// to the right.
+    ///
+    /// # Errors
+    ///
+    /// If the element cannot be inserted into the list due to capacity overflow,
+    /// the element is returned back in an `Err`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length of this list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![allow(deprecated)]
+    /// use storagevec::StorageVec;
+    ///
+    /// let mut list: StorageVec<i32, 2> = StorageVec::new();
+    /// assert_eq!(list.try_insert_at(0, 2), Ok(()));
+    /// assert_eq!(list.try_insert_at(0, 1), Ok(()));
+    ///
+    /// // without the `alloc` feature, the list cannot grow past `N`
+    /// #[cfg(not(feature = "alloc"))]
+    /// {
+    ///     assert_eq!(list.try_insert_at(1, 3), Err(3));
+    ///     assert_eq!(&*list, &[1, 2]);
+    /// }
+    /// ```
+    #[inline]
+    pub fn try_insert_at(&mut self, index: usize, item: T) -> Result<(), T> {
+        self.try_insert_impl(index, item)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_insert_impl(&mut self, index: usize, item: T) -> Result<(), T> {
+        (self.0).0.insert(index, item);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_insert_impl(&mut self, index: usize, item: T) -> Result<(), T> {
+        match (self.0).0.try_insert(index, item) {
+            None => Ok(()),
+            Some(reject) => Err(reject),
+        }
+    }
+
+    /// Insert an item into this list at the given index, shifting all elements after it to the
+    /// right, and panic if the insert operation fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length of this list, or if the `alloc` feature is
+    /// disabled and the list is full. Use `try_insert_at` to handle the latter case instead.
+    #[inline]
+    pub fn insert_at(&mut self, index: usize, item: T) {
+        if let Err(_) = self.try_insert_at(index, item) {
+            panic!("<StorageVec> Failed to insert item into list due to capacity overflow");
+        }
+    }
+
+    /// Insert an item into this list at the given index, shifting every element at or after
+    /// `index` one place to the right. Unlike `swap_remove`, this keeps the other elements in
+    /// their original order. This is the same as `try_insert_at`.
+    ///
+    /// # Errors
+    ///
+    /// If the `alloc` feature is disabled and the list is full, the list is left unchanged and
+    /// the element is returned back in an `Err`. This never fails when the `alloc` feature is
+    /// enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length of this list.
+    #[inline]
+    pub fn shift_insert(&mut self, index: usize, item: T) -> Result<(), T> {
+        self.try_insert_at(index, item)
+    }
+
+    /// Try to insert an item into this list.
+    ///
+    /// This takes its arguments in the opposite order to `Vec::insert`. Use `try_insert_at`,
+    /// which takes the index first, instead.
+    ///
+    /// # Errors
+    ///
+    /// If the element cannot be inserted into the list due to capacity overflow,
+    /// the element is returned back in an `Err`.
+    #[deprecated = "Use `try_insert_at`, which takes the index before the item."]
+    #[inline]
+    pub fn try_insert(&mut self, item: T, index: usize) -> Result<(), T> {
+        self.try_insert_at(index, item)
+    }
+
+    /// Insert an item into this list, and panic if the insert operation fails.
+    ///
+    /// This takes its arguments in the opposite order to `Vec::insert`. Use `insert_at`, which
+    /// takes the index first, instead.
+    #[deprecated = "Use `insert_at`, which takes the index before the item."]
+    #[inline]
+    pub fn insert(&mut self, item: T, index: usize) {
+        self.insert_at(index, item);
+    }
+
+    /// Remove an item from this list. Returns `None` if `index` is out of bounds. This never
+    /// panics, whichever backend is in use.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        // every backend panics on an out of bounds index, so this check has to come first
+        if index >= self.len() {
+            None
+        } else {
+            Some((self.0).0.remove(index))
+        }
+    }
+
+    /// Remove the first element that is equal to `item`, preserving the order of the rest.
+    /// Returns `None` if no element is equal to `item`.
+    #[inline]
+    pub fn remove_item(&mut self, item: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let index = self.position(|x| x == item)?;
+        self.remove(index)
+    }
+
+    /// Remove an item from this list by swapping it with the last item. This does not preserve
+    /// ordering, but is O(1). Returns `None` if `index` is out of bounds.
+    #[inline]
+    pub fn swap_remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            None
+        } else {
+            Some((self.0).0.swap_remove(index))
+        }
+    }
+
+    /// Shorten this list to `len` elements, dropping the rest. This has no effect if `len` is
+    /// greater than the current length.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        (self.0).0.truncate(len);
+    }
+
+    /// Try to resize this list to `new_len` elements. If the list grows, the new slots are
+    /// filled with clones of `value`. If it shrinks, it is truncated.
+    ///
+    /// # Errors
+    ///
+    /// If the `alloc` feature is disabled and `new_len` is greater than `N`, the list is left
+    /// unchanged and an `Err` is returned. This never fails when the `alloc` feature is enabled.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), ()>
+    where
+        T: Clone,
+    {
+        self.try_resize_impl(new_len, value)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_wraps)]
+    #[inline]
+    fn try_resize_impl(&mut self, new_len: usize, value: T) -> Result<(), ()>
+    where
+        T: Clone,
+    {
+        (self.0).0.resize(new_len, value);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_resize_impl(&mut self, new_len: usize, value: T) -> Result<(), ()>
+    where
+        T: Clone,
+    {
+        if new_len > N {
+            Err(())
+        } else {
+            (self.0).0.resize(new_len, value);
+            Ok(())
+        }
+    }
+
+    /// Resize this list to `new_len` elements, and panic if the list cannot hold that many
+    /// elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and `new_len` is greater than `N`. Use
+    /// `try_resize` to handle this case instead.
+    #[inline]
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if let Err(()) = self.try_resize(new_len, value) {
+            panic!("<StorageVec> Failed to resize list due to capacity overflow");
+        }
+    }
+
+    /// Try to resize this list to `new_len` elements. If the list grows, the new slots are
+    /// filled by calling `f`. If it shrinks, it is truncated.
+    ///
+    /// # Errors
+    ///
+    /// If the `alloc` feature is disabled and `new_len` is greater than `N`, the list is left
+    /// unchanged and an `Err` is returned. This never fails when the `alloc` feature is enabled.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_resize_with<F: FnMut() -> T>(&mut self, new_len: usize, f: F) -> Result<(), ()> {
+        self.try_resize_with_impl(new_len, f)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_wraps)]
+    #[inline]
+    fn try_resize_with_impl<F: FnMut() -> T>(&mut self, new_len: usize, f: F) -> Result<(), ()> {
+        (self.0).0.resize_with(new_len, f);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_resize_with_impl<F: FnMut() -> T>(&mut self, new_len: usize, f: F) -> Result<(), ()> {
+        if new_len > N {
+            Err(())
+        } else {
+            (self.0).0.resize_with(new_len, f);
+            Ok(())
+        }
+    }
+
+    /// Resize this list to `new_len` elements, filling new slots by calling `f`, and panic if
+    /// the list cannot hold that many elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and `new_len` is greater than `N`. Use
+    /// `try_resize_with` to handle this case instead.
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, f: F) {
+        if let Err(()) = self.try_resize_with(new_len, f) {
+            panic!("<StorageVec> Failed to resize list due to capacity overflow");
+        }
+    }
+
+    /// Try to resize this list to `new_len` elements. If the list grows, the new slots are
+    /// filled with `T::default()`. If it shrinks, it is truncated.
+    ///
+    /// # Errors
+    ///
+    /// If the `alloc` feature is disabled and `new_len` is greater than `N`, the list is left
+    /// unchanged and an `Err` is returned. This never fails when the `alloc` feature is enabled.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_resize_default(&mut self, new_len: usize) -> Result<(), ()>
+    where
+        T: Default,
+    {
+        self.try_resize_with(new_len, T::default)
+    }
+
+    /// Resize this list to `new_len` elements, filling new slots with `T::default()`, and panic
+    /// if the list cannot hold that many elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and `new_len` is greater than `N`. Use
+    /// `try_resize_default` to handle this case instead.
+    #[inline]
+    pub fn resize_default(&mut self, new_len: usize)
+    where
+        T: Default,
+    {
+        self.resize_with(new_len, T::default);
+    }
+
+    /// Retain only the elements that satisfy the predicate `f`, dropping the rest. Elements are
+    /// visited in order.
+    #[inline]
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        (self.0).0.retain(f);
+    }
+
+    /// Retain only the elements that satisfy the predicate `f`, dropping the rest. Unlike
+    /// `retain`, the predicate may also modify the elements it is given. Elements are visited
+    /// in order.
+    #[inline]
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let slice = self.deref_mut_impl();
+
+        // compact the kept elements to the front of the slice, then drop the rest
+        let mut write = 0;
+        for read in 0..slice.len() {
+            if f(&mut slice[read]) {
+                slice.swap(read, write);
+                write += 1;
+            }
+        }
+        self.truncate(write);
+    }
+
+    /// Try to push every item of `iter` onto the end of this list. Unlike the `Extend`
+    /// implementation, this does not panic if the list runs out of space.
+    ///
+    /// # Errors
+    ///
+    /// If an item cannot be pushed due to capacity overflow, that item is returned in an `Err`.
+    /// Every item yielded before it has already been pushed onto the list, so the list will be
+    /// full, and the rest of the iterator is left unconsumed. This never fails when the `alloc`
+    /// feature is enabled.
+    #[inline]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), T> {
+        for item in iter {
+            self.try_push(item)?;
+        }
+        Ok(())
+    }
+
+    /// Push items of `iter` onto the end of this list until it is full, and return the number
+    /// of items that were pushed. Unlike `try_extend`, running out of space is not an error;
+    /// the rest of the iterator is simply left unconsumed. When the `alloc` feature is enabled,
+    /// the list never fills up, so every item is pushed.
+    #[inline]
+    pub fn fill_from_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        let len = self.len();
+        self.fill_from_iter_impl(iter);
+        self.len() - len
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn fill_from_iter_impl<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        (self.0).0.extend(iter);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn fill_from_iter_impl<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let remaining = N - self.len();
+        (self.0).0.extend(iter.into_iter().take(remaining));
+    }
+
+    /// Try to clone every element of `other` onto the end of this list.
+    ///
+    /// # Errors
+    ///
+    /// If the `alloc` feature is disabled and the elements would not fit within `N`, the list
+    /// is left unchanged and an `Err` is returned. This never fails when the `alloc` feature is
+    /// enabled.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), ()>
+    where
+        T: Clone,
+    {
+        self.try_extend_from_slice_impl(other)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_wraps)]
+    #[inline]
+    fn try_extend_from_slice_impl(&mut self, other: &[T]) -> Result<(), ()>
+    where
+        T: Clone,
+    {
+        (self.0).0.extend_from_slice(other);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_extend_from_slice_impl(&mut self, other: &[T]) -> Result<(), ()>
+    where
+        T: Clone,
+    {
+        if self.len() + other.len() > N {
+            Err(())
+        } else {
+            (self.0).0.extend_from_slice(other);
+            Ok(())
+        }
+    }
+
+    /// Clone every element of `other` onto the end of this list, and panic if they do not fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and the elements would not fit within `N`. Use
+    /// `try_extend_from_slice` to handle this case instead.
+    #[inline]
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        if let Err(()) = self.try_extend_from_slice(other) {
+            panic!("<StorageVec> Failed to extend list due to capacity overflow");
+        }
+    }
+
+    /// Try to clone the elements of every sub-list in this list onto the end of `out`, in order.
+    /// The sub-lists can be anything that can be viewed as a slice, such as arrays or other
+    /// `StorageVec`s.
+    ///
+    /// # Errors
+    ///
+    /// If `out` cannot hold every element, it is left unchanged and an `Err` is returned. When
+    /// the `alloc` feature is enabled, this only fails if allocation fails.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_flatten_into<U: Clone + StorageElement, const M: usize>(
+        &self,
+        out: &mut StorageVec<U, M>,
+    ) -> Result<(), ()>
+    where
+        T: AsRef<[U]>,
+    {
+        let total = self
+            .deref_impl()
+            .iter()
+            .map(|list| list.as_ref().len())
+            .sum();
+        out.try_reserve(total).map_err(|_| ())?;
+        for list in self.deref_impl() {
+            out.extend_from_slice(list.as_ref());
+        }
+        Ok(())
+    }
+
+    /// Clone the elements of every sub-list in this list onto the end of `out`, in order, and
+    /// panic if they do not fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and the elements would not fit within `M`.
+    /// Use `try_flatten_into` to handle this case instead.
+    #[inline]
+    pub fn flatten_into<U: Clone + StorageElement, const M: usize>(
+        &self,
+        out: &mut StorageVec<U, M>,
+    ) where
+        T: AsRef<[U]>,
+    {
+        if let Err(()) = self.try_flatten_into(out) {
+            panic!("<StorageVec> Failed to flatten list due to capacity overflow");
+        }
+    }
+
+    /// Try to create a new list by repeating the elements of this list `n` times.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the length of the new list would overflow a `usize`, if the
+    /// allocation fails, or if the `alloc` feature is disabled and the new list would not fit
+    /// within `N`.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_repeat(&self, n: usize) -> Result<Self, ()>
+    where
+        T: Clone,
+    {
+        let total = self.len().checked_mul(n).ok_or(())?;
+        let mut repeated = Self::new();
+        repeated.try_reserve(total).map_err(|_| ())?;
+        for _ in 0..n {
+            repeated.extend_from_slice(self);
+        }
+        Ok(repeated)
+    }
+
+    /// Create a new list by repeating the elements of this list `n` times, and panic if it does
+    /// not fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the new list would overflow a `usize`, or if the `alloc` feature
+    /// is disabled and the new list would not fit within `N`. Use `try_repeat` to handle these
+    /// cases instead.
+    #[inline]
+    #[must_use]
+    pub fn repeat(&self, n: usize) -> Self
+    where
+        T: Clone,
+    {
+        match self.try_repeat(n) {
+            Ok(repeated) => repeated,
+            Err(()) => panic!("<StorageVec> Failed to repeat list due to capacity overflow"),
+        }
+    }
+
+    /// Split this list in two at the given index. The returned list contains the elements in
+    /// `[at, len)`, while this list keeps the elements in `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is greater than the length of this list.
+    #[inline]
+    #[must_use]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        self.split_off_impl(at)
+    }
+
+    #[cfg(any(not(feature = "alloc"), feature = "stack"))]
+    #[inline]
+    fn split_off_impl(&mut self, at: usize) -> Self {
+        Self(SVImpl((self.0).0.split_off(at)))
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    #[inline]
+    fn split_off_impl(&mut self, at: usize) -> Self {
+        Self(SVImpl((self.0).0.split_off(at), PhantomData))
+    }
+
+    /// Try to move every element of `other` onto the end of this list, leaving `other` empty.
+    ///
+    /// # Errors
+    ///
+    /// If the `alloc` feature is disabled and the elements would not fit within `N`, both lists
+    /// are left unchanged and an `Err` is returned. This never fails when the `alloc` feature
+    /// is enabled.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), ()> {
+        self.try_append_impl(other)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_wraps)]
+    #[inline]
+    fn try_append_impl(&mut self, other: &mut Self) -> Result<(), ()> {
+        (self.0).0.append(&mut (other.0).0);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_append_impl(&mut self, other: &mut Self) -> Result<(), ()> {
+        match (self.0).0.try_append(&mut (other.0).0) {
+            None => Ok(()),
+            Some(_) => Err(()),
+        }
+    }
+
+    /// Move every element of `other` onto the end of this list, leaving `other` empty, and
+    /// panic if they do not fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `alloc` feature is disabled and the elements would not fit within `N`. Use
+    /// `try_append` to handle this case instead.
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        if let Err(()) = self.try_append(other) {
+            panic!("<StorageVec> Failed to append list due to capacity overflow");
+        }
+    }
+
+    /// Remove consecutive elements that are equal to each other, keeping the first of each
+    /// run.
+    #[inline]
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Remove consecutive elements that map to the same key, keeping the first of each run.
+    #[inline]
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Remove consecutive elements that satisfy the predicate `same`, keeping the first of
+    /// each run. `same` is passed the current element and the last element that was kept, in
+    /// that order.
+    #[inline]
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same: F) {
+        let slice = self.deref_mut_impl();
+        if slice.len() <= 1 {
+            return;
+        }
+
+        // compact the kept elements to the front of the slice, then drop the rest
+        let mut write = 1;
+        for read in 1..slice.len() {
+            let (kept, rest) = slice.split_at_mut(read);
+            if !same(&mut rest[0], &mut kept[write - 1]) {
+                slice.swap(read, write);
+                write += 1;
+            }
+        }
+        self.truncate(write);
+    }
+
+    /// Consume this list and leak its elements, returning a mutable slice that lives for as
+    /// long as `'a`. This intentionally leaks memory; the elements are never dropped. With the
+    /// `stack` feature, elements that are still inline are moved to the heap first.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    #[must_use]
+    pub fn leak<'a>(self) -> &'a mut [T]
+    where
+        T: 'a,
+    {
+        self.into_vec().leak()
+    }
+
+    /// Remove every element that is equal to an earlier element, keeping the first occurrence of
+    /// each value in its original order. Unlike `dedup`, the duplicates do not need to be
+    /// consecutive.
+    ///
+    /// This requires the `alloc` feature, since it allocates a `HashSet` to keep track of the
+    /// values that have already been seen.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn dedup_all(&mut self)
+    where
+        T: Eq + Hash,
+    {
+        let keep: Vec<bool> = {
+            let mut seen = HashSet::with_capacity(self.len());
+            self.iter().map(|item| seen.insert(item)).collect()
+        };
+        let mut keep = keep.into_iter();
+        self.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// Consume this list and apply `f` to each of its elements in order, collecting the results
+    /// into a new list with the same capacity. Since the length is unchanged, this never
+    /// overflows.
+    #[inline]
+    #[must_use]
+    pub fn map<U: StorageElement, F: FnMut(T) -> U>(self, f: F) -> StorageVec<U, N> {
+        let mut list = StorageVec::with_capacity(self.len());
+        for item in self.into_iter().map(f) {
+            list.push(item);
+        }
+        list
+    }
+
+    /// Convert this list into a `Vec`. When the elements are already on the heap, the buffer is
+    /// moved into the `Vec` without copying. With the `stack` feature, a list whose elements
+    /// are still inline has to allocate a new buffer.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    #[must_use]
+    pub fn into_vec(self) -> Vec<T> {
+        self.into()
+    }
+
+    /// Remove every element for which `f` returns `true` and get an iterator over the removed
+    /// elements, in order. The predicate may also modify the elements it is given. Elements
+    /// for which `f` returns `false` are kept in their original order.
+    ///
+    /// The matching elements are removed before this method returns, so they are removed even
+    /// if the iterator is dropped before it is exhausted. The list keeps its buffer, and if `f`
+    /// panics, every element that has not been removed yet stays in the list.
+    #[inline]
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, f: F) -> StorageVecExtractIf<T, N> {
+        StorageVecExtractIf(self.extract_if_impl(f).into_iter())
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    #[inline]
+    fn extract_if_impl<F: FnMut(&mut T) -> bool>(&mut self, f: F) -> Self {
+        (self.0).0.extract_if(.., f).collect()
+    }
+
+    #[cfg(all(feature = "alloc", feature = "stack"))]
+    #[inline]
+    fn extract_if_impl<F: FnMut(&mut T) -> bool>(&mut self, f: F) -> Self {
+        if let TinyVec::Heap(list) = &mut (self.0).0 {
+            return list.extract_if(.., f).collect();
+        }
+        self.extract_inline(f)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn extract_if_impl<F: FnMut(&mut T) -> bool>(&mut self, f: F) -> Self {
+        self.extract_inline(f)
+    }
+
+    /// `extract_if` for the inline backends. The matching elements are moved out and replaced
+    /// with defaults, while the kept ones are compacted to the front the same way `retain_mut`
+    /// does it.
+    #[cfg(any(not(feature = "alloc"), feature = "stack"))]
+    #[inline]
+    fn extract_inline<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) -> Self {
+        // drains the defaults left behind by the extracted elements, even if `f` panics
+        struct Compact<'a, T: StorageElement, const N: usize> {
+            list: &'a mut StorageVec<T, N>,
+            read: usize,
+            write: usize,
+        }
+
+        impl<T: StorageElement, const N: usize> Drop for Compact<'_, T, N> {
+            #[inline]
+            fn drop(&mut self) {
+                self.list.drain(self.write..self.read);
+            }
+        }
+
+        // an inline list holds at most `N` elements, so this never spills
+        let mut extracted = Self::new();
+        let mut compact = Compact {
+            list: self,
+            read: 0,
+            write: 0,
+        };
+        while compact.read < compact.list.len() {
+            let (read, write) = (compact.read, compact.write);
+            let slice = compact.list.deref_mut_impl();
+            if f(&mut slice[read]) {
+                extracted.push(mem::take(&mut slice[read]));
+            } else {
+                slice.swap(read, write);
+                compact.write += 1;
+            }
+            compact.read += 1;
+        }
+        extracted
+    }
+
+    /// Try to replace the elements in `range` with the elements of `replace_with`, returning
+    /// an iterator over the removed elements.
+    ///
+    /// # Errors
+    ///
+    /// If the `alloc` feature is disabled and the resulting list would not fit within `N`, the
+    /// list is left unchanged and an `Err` is returned. This never fails when the `alloc`
+    /// feature is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the end of the range is
+    /// greater than the length of this list.
+    #[allow(clippy::result_unit_err)]
+    #[inline]
+    pub fn try_splice<R: RangeBounds<usize>, I: IntoIterator<Item = T>>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Result<StorageVecIterator<T, N>, ()> {
+        self.try_splice_impl(range, replace_with)
+            .map(StorageVecIterator::new)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_wraps)]
+    #[inline]
+    fn try_splice_impl<R: RangeBounds<usize>, I: IntoIterator<Item = T>>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Result<Self, ()> {
+        Ok((self.0).0.splice(range, replace_with).collect())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn try_splice_impl<R: RangeBounds<usize>, I: IntoIterator<Item = T>>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Result<Self, ()> {
+        let start = match range.start_bound() {
+            Bound::Included(&x) => x,
+            Bound::Excluded(&x) => x.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&x) => x.saturating_add(1),
+            Bound::Excluded(&x) => x,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(
+            start <= end,
+            "<StorageVec> Splice range starts at {} but ends at {}",
+            start,
+            end
+        );
+        assert!(
+            end <= self.len(),
+            "<StorageVec> Splice range end {} is out of bounds for length {}",
+            end,
+            self.len()
+        );
+
+        // collect the replacement first so nothing is touched if it overflows
+        let replacement = Self::try_from_iter(replace_with).map_err(|_| ())?;
+        if self.len() - (end - start) + replacement.len() > self.capacity() {
+            return Err(());
+        }
+
+        Ok((self.0).0.splice(start..end, replacement).collect())
+    }
+
+    /// Replace the elements in `range` with the elements of `replace_with`, returning an
+    /// iterator over the removed elements, and panic if the result does not fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or if the `alloc` feature is disabled and the
+    /// resulting list would not fit within `N`. Use `try_splice` to handle the latter case
+    /// instead.
+    #[inline]
+    pub fn splice<R: RangeBounds<usize>, I: IntoIterator<Item = T>>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> StorageVecIterator<T, N> {
+        match self.try_splice(range, replace_with) {
+            Ok(removed) => removed,
+            Err(()) => panic!("<StorageVec> Failed to splice list due to capacity overflow"),
+        }
+    }
+
+    /// Create a drain iterator for this vector.
+    #[inline]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> StorageVecDrain<'_, T, N> {
+        self.drain_impl(range)
+    }
+
+    #[cfg(all(feature = "alloc", feature = "stack"))]
+    #[inline]
+    fn drain_impl<R: RangeBounds<usize>>(&mut self, range: R) -> StorageVecDrain<'_, T, N> {
+        StorageVecDrain(SVDrainImpl((self.0).0.drain(range)))
+    }
+
+    #[cfg(any(not(feature = "alloc"), not(feature = "stack")))]
+    #[inline]
+    fn drain_impl<R: RangeBounds<usize>>(&mut self, range: R) -> StorageVecDrain<'_, T, N> {
+        StorageVecDrain(SVDrainImpl((self.0).0.drain(range), PhantomData))
+    }
+}
+
+/// A draining iterator for the `StorageVec`. Returned by `StorageVec::drain`.
+#[repr(transparent)]
+pub struct StorageVecDrain<'a, T: StorageElement, const N: usize>(SVDrainImpl<'a, T, N>);
+
+#[cfg(not(feature = "alloc"))]
+#[repr(transparent)]
+struct SVDrainImpl<'a, T: StorageElement, const N: usize>(
+    ArrayVecDrain<'a, T>,
+    PhantomData<[(); N]>,
+);
+
+#[cfg(all(feature = "alloc", not(feature = "stack")))]
+#[repr(transparent)]
+struct SVDrainImpl<'a, T: StorageElement, const N: usize>(vec::Drain<'a, T>, PhantomData<[(); N]>);
+
+#[cfg(all(feature = "alloc", feature = "stack"))]
+#[repr(transparent)]
+struct SVDrainImpl<'a, T: StorageElement, const N: usize>(TinyVecDrain<'a, [T; N]>);
+
+impl<T: StorageElement, const N: usize> Iterator for StorageVecDrain<'_, T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        (self.0).0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0).0.size_hint()
+    }
+}
+
+impl<T: StorageElement, const N: usize> DoubleEndedIterator for StorageVecDrain<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        (self.0).0.next_back()
+    }
+}
+
+impl<T: StorageElement, const N: usize> ExactSizeIterator for StorageVecDrain<'_, T, N> {}
+
+/// An iterator over the elements removed from a `StorageVec`. Returned by
+/// `StorageVec::extract_if`.
+#[repr(transparent)]
+pub struct StorageVecExtractIf<T: StorageElement, const N: usize>(StorageVecIterator<T, N>);
+
+impl<T: StorageElement, const N: usize> Iterator for StorageVecExtractIf<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// An owning iterator for the `StorageVec`. Returned by `StorageVec::into_iter`.
+#[repr(transparent)]
+pub struct StorageVecIterator<T: StorageElement, const N: usize>(SVIterImpl<T, N>);
+
+#[cfg(not(feature = "alloc"))]
+#[repr(transparent)]
+struct SVIterImpl<T: StorageElement, const N: usize>(ArrayVecIterator<[T; N]>);
+
+#[cfg(all(feature = "alloc", not(feature = "stack")))]
+#[repr(transparent)]
+struct SVIterImpl<T: StorageElement, const N: usize>(vec::IntoIter<T>, PhantomData<[(); N]>);
+
+#[cfg(all(feature = "alloc", feature = "stack"))]
+#[repr(transparent)]
+struct SVIterImpl<T: StorageElement, const N: usize>(TinyVecIterator<[T; N]>);
+
+impl<T: StorageElement, const N: usize> StorageVecIterator<T, N> {
+    #[cfg(any(not(feature = "alloc"), feature = "stack"))]
+    #[inline]
+    fn new(list: StorageVec<T, N>) -> Self {
+        Self(SVIterImpl((list.0).0.into_iter()))
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    #[inline]
+    fn new(list: StorageVec<T, N>) -> Self {
+        Self(SVIterImpl((list.0).0.into_iter(), PhantomData))
+    }
+}
+
+impl<T: StorageElement, const N: usize> Iterator for StorageVecIterator<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        (self.0).0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0).0.size_hint()
+    }
+}
+
+impl<T: StorageElement, const N: usize> ExactSizeIterator for StorageVecIterator<T, N> {}
+
+#[cfg(not(feature = "stack"))]
+impl<T: StorageElement, const N: usize> DoubleEndedIterator for StorageVecIterator<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        (self.0).0.next_back()
+    }
+}
+
+impl<T: StorageElement, const N: usize> ops::Deref for StorageVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.deref_impl()
+    }
+}
+
+impl<T: Clone + StorageElement, const N: usize> Clone for StorageVec<T, N> {
+    #[cfg(any(not(feature = "alloc"), feature = "stack"))]
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(SVImpl((self.0).0.clone()))
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(SVImpl((self.0).0.clone(), PhantomData))
+    }
+}
+
+impl<T: StorageElement + PartialEq, const N: usize> PartialEq for StorageVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.deref_impl() == other.deref_impl()
+    }
+}
+
+impl<T: StorageElement + Eq, const N: usize> Eq for StorageVec<T, N> {}
+
+impl<T: StorageElement + PartialOrd, const N: usize> PartialOrd for StorageVec<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.deref_impl().partial_cmp(other.deref_impl())
+    }
+}
+
+impl<T: StorageElement + Ord, const N: usize> Ord for StorageVec<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deref_impl().cmp(other.deref_impl())
+    }
+}
+
+impl<T: StorageElement + PartialEq, const N: usize> PartialEq<[T]> for StorageVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &[T]) -> bool {
+        self.deref_impl() == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: StorageElement + PartialEq, const N: usize> PartialEq<Vec<T>> for StorageVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.deref_impl() == other.as_slice()
+    }
+}
+
+impl<T: StorageElement + Hash, const N: usize> Hash for StorageVec<T, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref_impl().hash(state);
+    }
+}
+
+impl<T: StorageElement, const N: usize> ops::DerefMut for StorageVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.deref_mut_impl()
+    }
+}
+
+impl<T: StorageElement, const N: usize> AsRef<[T]> for StorageVec<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.deref_impl()
+    }
+}
+
+impl<T: StorageElement, const N: usize> AsMut<[T]> for StorageVec<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self.deref_mut_impl()
+    }
+}
+
+impl<T: StorageElement, const N: usize> Borrow<[T]> for StorageVec<T, N> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        self.deref_impl()
+    }
+}
+
+impl<T: StorageElement, const N: usize> BorrowMut<[T]> for StorageVec<T, N> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.deref_mut_impl()
+    }
+}
+
+impl<T: StorageElement, I: SliceIndex<[T]>, const N: usize> ops::Index<I> for StorageVec<T, N> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &I::Output {
+        &self.deref_impl()[index]
+    }
+}
+
+impl<T: StorageElement, I: SliceIndex<[T]>, const N: usize> ops::IndexMut<I> for StorageVec<T, N> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.deref_mut_impl()[index]
+    }
+}
+
+impl<T: StorageElement, const N: usize> iter::IntoIterator for StorageVec<T, N> {
+    type Item = T;
+    type IntoIter = StorageVecIterator<T, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        StorageVecIterator::new(self)
+    }
+}
+
+impl<'a, T: StorageElement, const N: usize> iter::IntoIterator for &'a StorageVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_impl().iter()
+    }
+}
+
+impl<'a, T: StorageElement, const N: usize> iter::IntoIterator for &'a mut StorageVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T: StorageElement, const N: usize> iter::Extend<T> for StorageVec<T, N> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        (self.0).0.extend(iter)
+    }
+}
+
+/// # Panics
+///
+/// If the `alloc` feature is disabled, this panics if the iterator yields more than `N` items.
+/// Use `StorageVec::try_from_iter` to handle this case instead.
+impl<T: StorageElement, const N: usize> iter::FromIterator<T> for StorageVec<T, N> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut collection = Self::new();
+        collection.extend(iter);
+        collection
+    }
+}
+
+impl<T: StorageElement, const N: usize> From<[T; N]> for StorageVec<T, N> {
+    #[inline]
+    fn from(array: [T; N]) -> Self {
+        Self::from_array(array)
+    }
+}
+
+impl<T: Clone + StorageElement, const N: usize> TryFrom<&[T]> for StorageVec<T, N> {
+    type Error = TryFromSliceError;
+
+    /// Clone every element of `slice` into a new `StorageVec`. This fails if the `alloc`
+    /// feature is disabled and `slice` has more than `N` elements.
+    #[inline]
+    fn try_from(slice: &[T]) -> Result<Self, TryFromSliceError> {
+        let mut list = Self::new();
+        match list.try_extend_from_slice(slice) {
+            Ok(()) => Ok(list),
+            Err(()) => Err(TryFromSliceError(())),
+        }
+    }
+}
+
+/// The error returned when a slice cannot be converted into a `StorageVec` because it has more
+/// than `N` elements. This only happens when the `alloc` feature is disabled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TryFromSliceError(());
+
+impl fmt::Display for TryFromSliceError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("slice is too long to fit in the StorageVec")
+    }
+}
+
+/// The error returned by `StorageVec::try_reserve`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity could not be represented, or the `alloc` feature is disabled and
+    /// the list would need to hold more than `N` elements.
+    CapacityOverflow,
+    /// The allocator failed to provide the requested memory.
+    AllocError,
+}
+
+impl TryReserveError {
+    /// Tell why reserving `additional` more elements for a list of length `len` failed. Only a
+    /// capacity that fits in a `Layout` could have been handed to the allocator.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn classify<T>(len: usize, additional: usize) -> Self {
+        match len.checked_add(additional).map(Layout::array::<T>) {
+            Some(Ok(_)) => Self::AllocError,
+            _ => Self::CapacityOverflow,
+        }
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CapacityOverflow => "capacity overflow while reserving space in the StorageVec",
+            Self::AllocError => "memory allocation failed while reserving space in the StorageVec",
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: StorageElement, const N: usize> From<Vec<T>> for StorageVec<T, N> {
+    #[cfg(not(feature = "stack"))]
+    #[inline]
+    fn from(list: Vec<T>) -> Self {
+        Self(SVImpl(list, PhantomData))
+    }
+
+    #[cfg(feature = "stack")]
+    #[inline]
+    fn from(list: Vec<T>) -> Self {
+        Self(SVImpl(TinyVec::Heap(list)))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: StorageElement, const N: usize> From<StorageVec<T, N>> for Vec<T> {
+    #[cfg(not(feature = "stack"))]
+    #[inline]
+    fn from(list: StorageVec<T, N>) -> Self {
+        (list.0).0
+    }
+
+    #[cfg(feature = "stack")]
+    #[inline]
+    fn from(list: StorageVec<T, N>) -> Self {
+        match (list.0).0 {
+            TinyVec::Heap(list) => list,
+            TinyVec::Inline(list) => list.into_iter().collect(),
+        }
+    }
+}
+
+/// Writing a string pushes its UTF-8 bytes onto the end of the list. If the `alloc` feature is
+/// disabled and the bytes do not fit, the list is left unchanged and `fmt::Error` is returned.
+impl<const N: usize> fmt::Write for StorageVec<u8, N> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.try_extend_from_slice(s.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(()) => Err(fmt::Error),
+        }
+    }
+}
+
+/// Writing pushes as many bytes as possible onto the end of the list. If the `alloc` feature is
+/// disabled, a write may be partial once the list is nearly full, and `write_all` fails with
+/// `ErrorKind::WriteZero` once it is full.
+#[cfg(feature = "std")]
+impl<const N: usize> io::Write for StorageVec<u8, N> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let amount = match self.try_reserve(buf.len()) {
+            Ok(()) => buf.len(),
+            Err(_) => self.capacity() - self.len(),
+        };
+        self.extend_from_slice(&buf[..amount]);
+        Ok(amount)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: StorageElement, const N: usize> Default for StorageVec<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: StorageElement + fmt::Debug, const N: usize> fmt::Debug for StorageVec<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&(self.0).0, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: StorageElement + Serialize, const N: usize> Serialize for StorageVec<T, N> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.deref_impl())
+    }
+}
+
+#[cfg(feature = "serde")]
+struct StorageVecVisitor<T, const N: usize>(PhantomData<[T; N]>);
+
+#[cfg(feature = "serde")]
+impl<'de, T: StorageElement + Deserialize<'de>, const N: usize> Visitor<'de>
+    for StorageVecVisitor<T, N>
+{
+    type Value = StorageVec<T, N>;
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of at most {N} elements")
+    }
+
+    #[inline]
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut list = StorageVec::new();
+        while let Some(item) = seq.next_element()? {
+            if let Err(_) = list.try_push(item) {
+                return Err(de::Error::invalid_length(list.len() + 1, &self));
+            }
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: StorageElement + Deserialize<'de>, const N: usize> Deserialize<'de>
+    for StorageVec<T, N>
+{
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(StorageVecVisitor(PhantomData))
+    }
+}
+
+// These tests run against whichever backend the enabled features select, so CI runs them once
+// for each feature combination.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list<const N: usize>(items: &[i32]) -> StorageVec<i32, N> {
+        items.iter().copied().collect()
+    }
+
+    #[test]
+    fn remove_last_element() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.remove(2), Some(3));
+        assert_eq!(list.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn remove_single_element() {
+        let mut list = list::<4>(&[1]);
+        assert_eq!(list.remove(0), Some(1));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn remove_from_empty() {
+        let mut list = list::<4>(&[]);
+        assert_eq!(list.remove(0), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn remove_out_of_bounds() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.remove(3), None);
+        assert_eq!(list.remove(usize::MAX), None);
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn try_insert_full_returns_item() {
+        let mut list = list::<3>(&[1, 2, 3]);
+        assert_eq!(list.try_insert_at(1, 4), Err(4));
+        #[allow(deprecated)]
+        let result = list.try_insert(5, 1);
+        assert_eq!(result, Err(5));
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn try_insert_with_room() {
+        let mut list = list::<4>(&[1, 3]);
+        assert_eq!(list.try_insert_at(1, 2), Ok(()));
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn clear_then_push() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        let capacity = list.capacity();
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.capacity(), capacity);
+        list.push(4);
+        assert_eq!(list.as_slice(), &[4]);
+    }
+
+    #[test]
+    fn capacity_of_new_list() {
+        let list: StorageVec<i32, 8> = StorageVec::new();
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(list.capacity(), 8);
+        #[cfg(all(feature = "alloc", feature = "stack"))]
+        assert_eq!(list.capacity(), 8);
+        #[cfg(all(feature = "alloc", not(feature = "stack")))]
+        assert_eq!(list.capacity(), 0);
+    }
+
+    #[test]
+    fn swap_remove_moves_last_element() {
+        let mut list = list::<4>(&[1, 2, 3, 4]);
+        assert_eq!(list.swap_remove(0), Some(1));
+        assert_eq!(list.as_slice(), &[4, 2, 3]);
+        assert_eq!(list.swap_remove(2), Some(3));
+        assert_eq!(list.as_slice(), &[4, 2]);
+    }
+
+    #[test]
+    fn swap_remove_out_of_bounds() {
+        assert_eq!(list::<4>(&[]).swap_remove(0), None);
+        let mut list = list::<4>(&[1, 2]);
+        assert_eq!(list.swap_remove(2), None);
+        assert_eq!(list.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn truncate_drops_tail() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        list.truncate(5);
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+        list.truncate(1);
+        assert_eq!(list.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn resize_grows_and_shrinks() {
+        let mut list = list::<4>(&[1]);
+        list.resize(4, 7);
+        assert_eq!(list.as_slice(), &[1, 7, 7, 7]);
+        list.resize(2, 0);
+        assert_eq!(list.as_slice(), &[1, 7]);
+    }
+
+    #[test]
+    fn try_resize_past_capacity() {
+        let mut list = list::<4>(&[1]);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(list.try_resize(5, 0), Err(()));
+            assert_eq!(list.as_slice(), &[1]);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(list.try_resize(5, 0), Ok(()));
+            assert_eq!(list.as_slice(), &[1, 0, 0, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn retain_evens() {
+        let mut list = list::<8>(&[1, 2, 3, 4, 5, 6]);
+        let mut seen = std::vec::Vec::new();
+        list.retain(|x| {
+            seen.push(*x);
+            x % 2 == 0
+        });
+        assert_eq!(list.as_slice(), &[2, 4, 6]);
+        assert_eq!(seen, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn retain_none_and_all() {
+        let mut list = list::<8>(&[1, 2, 3]);
+        list.retain(|_| true);
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+        list.retain(|_| false);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn retain_drops_removed_elements() {
+        let counter = std::rc::Rc::new(());
+        let mut list: StorageVec<std::rc::Rc<()>, 4> = StorageVec::new();
+        for _ in 0..4 {
+            list.push(counter.clone());
+        }
+        let mut index = 0;
+        list.retain(|_| {
+            index += 1;
+            index % 2 == 0
+        });
+        assert_eq!(list.len(), 2);
+        assert_eq!(std::rc::Rc::strong_count(&counter), 3);
+    }
+
+    #[test]
+    fn eq_compares_elements() {
+        let a = list::<4>(&[1, 2, 3]);
+        let b = list::<4>(&[1, 2, 3]);
+        let c = list::<4>(&[1, 2]);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(list::<4>(&[]), StorageVec::new());
+    }
+
+    #[test]
+    fn eq_against_slice() {
+        let a = list::<4>(&[1, 2, 3]);
+        assert_eq!(a, *[1, 2, 3].as_slice());
+        assert_ne!(a, *[1, 2].as_slice());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn eq_against_vec() {
+        let a = list::<4>(&[1, 2, 3]);
+        assert_eq!(a, alloc::vec![1, 2, 3]);
+        assert_ne!(a, alloc::vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn from_array_fills_to_capacity() {
+        let list = StorageVec::from([1, 2, 3]);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+        assert_eq!(StorageVec::from_array([1, 2, 3]), list);
+    }
+
+    #[test]
+    fn iter_mut_mutates_every_element() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        list.iter_mut().for_each(|item| *item *= 10);
+        assert_eq!(list.as_slice(), &[10, 20, 30]);
+    }
+
+    #[test]
+    fn try_from_iter_within_capacity() {
+        let exact: StorageVec<i32, 3> = StorageVec::try_from_iter(1..=3).unwrap();
+        assert_eq!(exact.as_slice(), &[1, 2, 3]);
+        let fewer: StorageVec<i32, 3> = StorageVec::try_from_iter(1..=2).unwrap();
+        assert_eq!(fewer.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn try_from_iter_past_capacity() {
+        let more = StorageVec::<i32, 3>::try_from_iter(1..=5);
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(more, Err(4));
+        #[cfg(feature = "alloc")]
+        assert_eq!(more.unwrap().as_slice(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn from_iter_past_capacity_panics() {
+        let _: StorageVec<i32, 3> = (1..=4).collect();
+    }
+
+    #[test]
+    fn extend_from_slice_within_capacity() {
+        let mut list = list::<4>(&[1]);
+        list.extend_from_slice(&[2, 3]);
+        assert_eq!(list.try_extend_from_slice(&[4]), Ok(()));
+        assert_eq!(list.as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn try_extend_from_slice_past_capacity() {
+        let mut list = list::<4>(&[1, 2]);
+        let result = list.try_extend_from_slice(&[3, 4, 5]);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(result, Err(()));
+            assert_eq!(list.as_slice(), &[1, 2]);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(result, Ok(()));
+            assert_eq!(list.as_slice(), &[1, 2, 3, 4, 5]);
+        }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic(expected = "<StorageVec> Failed to extend list due to capacity overflow")]
+    fn extend_from_slice_past_capacity_panics() {
+        let mut list = list::<2>(&[1, 2]);
+        list.extend_from_slice(&[3]);
+    }
+
+    #[test]
+    fn contains_position_and_find() {
+        let list = list::<4>(&[1, 2, 3]);
+        assert!(list.contains(&2));
+        assert!(!list.contains(&4));
+        assert_eq!(list.position(|x| *x == 3), Some(2));
+        assert_eq!(list.position(|x| *x == 4), None);
+        assert_eq!(list.find(|x| *x > 1), Some(&2));
+        assert_eq!(list.find(|x| *x > 3), None);
+    }
+
+    #[test]
+    fn first_and_last() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.first(), Some(&1));
+        assert_eq!(list.last(), Some(&3));
+        *list.first_mut().unwrap() = 10;
+        *list.last_mut().unwrap() = 30;
+        assert_eq!(list.as_slice(), &[10, 2, 30]);
+
+        let mut empty = StorageVec::<i32, 4>::new();
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.last(), None);
+        assert_eq!(empty.first_mut(), None);
+        assert_eq!(empty.last_mut(), None);
+    }
+
+    #[test]
+    fn split_off_at_start_middle_and_end() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        let tail = list.split_off(3);
+        assert!(tail.is_empty());
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+
+        let tail = list.split_off(1);
+        assert_eq!(tail.as_slice(), &[2, 3]);
+        assert_eq!(list.as_slice(), &[1]);
+
+        let tail = list.split_off(0);
+        assert_eq!(tail.as_slice(), &[1]);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "split")]
+    fn split_off_past_len_panics() {
+        let mut list = list::<4>(&[1, 2]);
+        let _ = list.split_off(3);
+    }
+
+    #[test]
+    fn append_preserves_order_and_empties_other() {
+        let mut other = list::<4>(&[3, 4]);
+        let mut list = list::<4>(&[1, 2]);
+        list.append(&mut other);
+        assert_eq!(list.as_slice(), &[1, 2, 3, 4]);
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn try_append_past_capacity() {
+        let mut other = list::<4>(&[4, 5]);
+        let mut list = list::<4>(&[1, 2, 3]);
+        let result = list.try_append(&mut other);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(result, Err(()));
+            assert_eq!(list.as_slice(), &[1, 2, 3]);
+            assert_eq!(other.as_slice(), &[4, 5]);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(result, Ok(()));
+            assert_eq!(list.as_slice(), &[1, 2, 3, 4, 5]);
+            assert!(other.is_empty());
+        }
+    }
+
+    #[test]
+    fn ordering_is_lexicographic() {
+        assert!(list::<4>(&[1, 2]) < list::<4>(&[1, 2, 3]));
+        assert!(list::<4>(&[1, 3]) > list::<4>(&[1, 2, 3]));
+        assert_eq!(list::<4>(&[1, 2]).cmp(&list::<4>(&[1, 2])), Ordering::Equal);
+        assert_eq!(
+            list::<4>(&[]).partial_cmp(&list::<4>(&[0])),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn dedup_removes_consecutive_runs() {
+        let mut list = list::<8>(&[1, 1, 2, 3, 3, 3, 1, 4]);
+        list.dedup();
+        assert_eq!(list.as_slice(), &[1, 2, 3, 1, 4]);
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn dedup_by_key_groups_by_key() {
+        let mut list = list::<8>(&[10, 11, 20, 25, 31, 30]);
+        list.dedup_by_key(|x| *x / 10);
+        assert_eq!(list.as_slice(), &[10, 20, 31]);
+    }
+
+    #[test]
+    fn dedup_by_compares_with_last_kept() {
+        let mut list = list::<8>(&[1, 2, 4, 5, 7]);
+        list.dedup_by(|a, b| *a - *b == 1);
+        assert_eq!(list.as_slice(), &[1, 4, 7]);
+    }
+
+    #[test]
+    fn sort_unstable_variants() {
+        let mut list = list::<8>(&[3, 1, 4, 1, 5]);
+        list.sort_unstable();
+        assert_eq!(list.as_slice(), &[1, 1, 3, 4, 5]);
+        list.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(list.as_slice(), &[5, 4, 3, 1, 1]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn stable_sort_variants() {
+        let mut list = list::<8>(&[3, 1, 4, 1, 5]);
+        list.sort();
+        assert_eq!(list.as_slice(), &[1, 1, 3, 4, 5]);
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list.as_slice(), &[5, 4, 3, 1, 1]);
+        list.sort_by_key(|x| *x % 3);
+        assert_eq!(list.as_slice(), &[3, 4, 1, 1, 5]);
+    }
+
+    #[test]
+    fn reverse_and_rotate() {
+        let mut list = list::<8>(&[1, 2, 3, 4, 5]);
+        list.reverse();
+        assert_eq!(list.as_slice(), &[5, 4, 3, 2, 1]);
+        list.rotate_left(2);
+        assert_eq!(list.as_slice(), &[3, 2, 1, 5, 4]);
+        list.rotate_right(1);
+        assert_eq!(list.as_slice(), &[4, 3, 2, 1, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "mid <= self.len()")]
+    fn rotate_left_past_len_panics() {
+        let mut list = list::<8>(&[1, 2, 3]);
+        list.rotate_left(4);
+    }
+
+    #[test]
+    fn fill_and_fill_with_keep_len() {
+        let mut list = list::<8>(&[1, 2, 3]);
+        list.fill(7);
+        assert_eq!(list.as_slice(), &[7, 7, 7]);
+        let mut next = 0;
+        list.fill_with(|| {
+            next += 1;
+            next
+        });
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn drain_from_both_ends() {
+        let mut list = list::<8>(&[1, 2, 3, 4, 5]);
+        {
+            let mut drain: StorageVecDrain<'_, i32, 8> = list.drain(1..4);
+            assert_eq!(drain.len(), 3);
+            assert_eq!(drain.next(), Some(2));
+            assert_eq!(drain.next_back(), Some(4));
+            assert_eq!(drain.len(), 1);
+            assert_eq!(drain.next(), Some(3));
+            assert_eq!(drain.next(), None);
+        }
+        assert_eq!(list.as_slice(), &[1, 5]);
+    }
+
+    #[test]
+    fn insert_at_uses_std_argument_order() {
+        let mut list = list::<8>(&[1, 3]);
+        list.insert_at(1, 2);
+        list.insert_at(0, 0);
+        list.insert_at(4, 4);
+        assert_eq!(list.as_slice(), &[0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index")]
+    fn insert_at_past_len_panics() {
+        let mut list = list::<8>(&[1, 2]);
+        list.insert_at(3, 0);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic(
+        expected = "<StorageVec> Failed to insert item into list due to capacity overflow"
+    )]
+    fn insert_at_into_full_list_panics() {
+        let mut list = list::<2>(&[1, 2]);
+        list.insert_at(0, 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vec_round_trip() {
+        let list: StorageVec<i32, 2> = StorageVec::from(alloc::vec![1, 2, 3]);
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+        let back: Vec<i32> = list.into();
+        assert_eq!(back, [1, 2, 3]);
+    }
+
+    #[test]
+    fn is_inline_for_each_backend() {
+        let mut list = list::<2>(&[1, 2]);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert!(list.is_inline());
+            assert_eq!(list.try_push(3), Err(3));
+            assert!(list.is_inline());
+        }
+        #[cfg(all(feature = "alloc", feature = "stack"))]
+        {
+            assert!(list.is_inline());
+            list.push(3);
+            assert!(!list.is_inline());
+        }
+        #[cfg(all(feature = "alloc", not(feature = "stack")))]
+        {
+            assert!(!list.is_inline());
+            list.push(3);
+            assert!(!list.is_inline());
+        }
+    }
+
+    #[test]
+    fn resize_with_counter() {
+        let mut list = list::<8>(&[1]);
+        let mut next = 1;
+        let mut counter = || {
+            next += 1;
+            next
+        };
+        list.resize_with(4, &mut counter);
+        assert_eq!(list.as_slice(), &[1, 2, 3, 4]);
+        list.resize_with(2, &mut counter);
+        assert_eq!(list.as_slice(), &[1, 2]);
+        assert_eq!(next, 4);
+    }
+
+    #[test]
+    fn try_resize_with_past_capacity() {
+        let mut list = list::<2>(&[1]);
+        let result = list.try_resize_with(3, || 0);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(result, Err(()));
+            assert_eq!(list.as_slice(), &[1]);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(result, Ok(()));
+            assert_eq!(list.as_slice(), &[1, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn as_ref_and_borrow_slices() {
+        fn sum<S: AsRef<[i32]>>(s: S) -> i32 {
+            s.as_ref().iter().sum()
+        }
+        fn zero_first<S: AsMut<[i32]>>(mut s: S) -> S {
+            s.as_mut()[0] = 0;
+            s
+        }
+
+        let list = list::<4>(&[1, 2, 3]);
+        assert_eq!(sum(&list), 6);
+        let list = zero_first(list);
+        assert_eq!(sum(list.clone()), 5);
+        let borrowed: &[i32] = core::borrow::Borrow::borrow(&list);
+        assert_eq!(borrowed, &[0, 2, 3]);
+    }
+
+    #[test]
+    fn binary_search_found_and_insertion_point() {
+        let list = list::<8>(&[1, 3, 5, 7]);
+        assert_eq!(list.binary_search(&5), Ok(2));
+        assert_eq!(list.binary_search(&4), Err(2));
+        assert_eq!(list.binary_search_by(|x| x.cmp(&7)), Ok(3));
+        assert_eq!(list.binary_search_by(|x| x.cmp(&8)), Err(4));
+        assert_eq!(list.binary_search_by_key(&6, |x| x * 2), Ok(1));
+        assert_eq!(list.binary_search_by_key(&0, |x| x * 2), Err(0));
+    }
+
+    #[test]
+    fn chunks_and_windows() {
+        let list = list::<8>(&[1, 2, 3, 4, 5]);
+        let mut chunks = list.chunks(2);
+        assert_eq!(chunks.next(), Some(&[1, 2][..]));
+        assert_eq!(chunks.next(), Some(&[3, 4][..]));
+        assert_eq!(chunks.next(), Some(&[5][..]));
+        assert_eq!(chunks.next(), None);
+        assert_eq!(list.windows(2).count(), 4);
+        assert_eq!(list.windows(5).next(), Some(list.as_slice()));
+    }
+
+    #[test]
+    fn swap_elements() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        list.swap(0, 2);
+        assert_eq!(list.as_slice(), &[3, 2, 1]);
+        list.swap(1, 1);
+        assert_eq!(list.as_slice(), &[3, 2, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn swap_out_of_bounds_panics() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        list.swap(0, 3);
+    }
+
+    #[test]
+    fn try_reserve_within_capacity() {
+        let mut list = list::<4>(&[1]);
+        assert_eq!(list.try_reserve(3), Ok(()));
+        assert!(list.capacity() >= 4);
+        assert_eq!(list.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn try_reserve_past_capacity() {
+        let mut list = list::<4>(&[1]);
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(list.try_reserve(4), Err(TryReserveError::CapacityOverflow));
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            list.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(list.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn rev_iter_and_rev_iter_mut() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert!(list.rev_iter().copied().eq([3, 2, 1].iter().copied()));
+        let mut next = 0;
+        list.rev_iter_mut().for_each(|item| {
+            next += 1;
+            *item = next;
+        });
+        assert_eq!(list.as_slice(), &[3, 2, 1]);
+    }
+
+    #[test]
+    fn shrink_after_drain() {
+        let count = if cfg!(feature = "alloc") { 64 } else { 4 };
+        let mut list: StorageVec<i32, 4> = (0..count).collect();
+        list.drain(2..);
+        list.shrink_to(16);
+        #[cfg(feature = "alloc")]
+        assert!(list.capacity() >= 16 && list.capacity() < 64);
+        list.shrink_to_fit();
+        assert_eq!(list.as_slice(), &[0, 1]);
+        #[cfg(all(feature = "alloc", not(feature = "stack")))]
+        assert_eq!(list.capacity(), 2);
+        #[cfg(any(not(feature = "alloc"), feature = "stack"))]
+        assert_eq!(list.capacity(), 4);
+    }
+
+    #[test]
+    fn split_first_and_last() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.split_first(), Some((&1, &[2, 3][..])));
+        assert_eq!(list.split_last(), Some((&3, &[1, 2][..])));
+        if let Some((first, rest)) = list.split_first_mut() {
+            *first += rest[0];
+        }
+        if let Some((last, _)) = list.split_last_mut() {
+            *last = 0;
+        }
+        assert_eq!(list.as_slice(), &[3, 2, 0]);
+
+        let mut empty = StorageVec::<i32, 4>::new();
+        assert_eq!(empty.split_first(), None);
+        assert_eq!(empty.split_last(), None);
+        assert!(empty.split_first_mut().is_none());
+        assert!(empty.split_last_mut().is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn into_vec_keeps_elements() {
+        assert_eq!(list::<4>(&[1, 2, 3]).into_vec(), [1, 2, 3]);
+        assert_eq!(list::<2>(&[1, 2, 3]).into_vec(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn try_extend_longer_than_capacity() {
+        let mut list = list::<4>(&[1]);
+        let mut iter = 2..=6;
+        let result = list.try_extend(&mut iter);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(result, Err(5));
+            assert_eq!(list.as_slice(), &[1, 2, 3, 4]);
+            assert_eq!(iter.next(), Some(6));
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(result, Ok(()));
+            assert_eq!(list.as_slice(), &[1, 2, 3, 4, 5, 6]);
+            assert_eq!(iter.next(), None);
+        }
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "stack")))]
+    #[test]
+    fn non_default_elements() {
+        #[derive(Debug, PartialEq)]
+        struct NoDefault(i32);
+
+        let mut list: StorageVec<NoDefault, 2> = StorageVec::new();
+        list.push(NoDefault(1));
+        list.push(NoDefault(2));
+        list.push(NoDefault(3));
+        assert_eq!(list.pop(), Some(NoDefault(3)));
+        assert_eq!(list.as_slice(), &[NoDefault(1), NoDefault(2)]);
+    }
+
+    #[test]
+    fn retain_mut_drops_and_mutates() {
+        let mut list = list::<8>(&[1, 2, 3, 4, 5, 6]);
+        list.retain_mut(|x| {
+            *x *= 10;
+            *x % 20 == 0
+        });
+        assert_eq!(list.as_slice(), &[20, 40, 60]);
+    }
+
+    #[test]
+    fn get_with_indices_and_ranges() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.get(1), Some(&2));
+        assert_eq!(list.get(3), None);
+        assert_eq!(list.get(1..), Some(&[2, 3][..]));
+        assert_eq!(list.get(2..5), None);
+        if let Some(tail) = list.get_mut(1..) {
+            tail[0] = 20;
+        }
+        assert_eq!(list.get_mut(usize::MAX), None);
+        assert_eq!(list.as_slice(), &[1, 20, 3]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn dedup_all_keeps_first_occurrences() {
+        let mut list = list::<8>(&[3, 1, 3, 2, 1, 4, 2]);
+        list.dedup_all();
+        assert_eq!(list.as_slice(), &[3, 1, 2, 4]);
+    }
+
+    #[test]
+    fn try_from_slice() {
+        let list = StorageVec::<i32, 3>::try_from(&[1, 2, 3][..]).unwrap();
+        assert_eq!(list.as_slice(), &[1, 2, 3]);
+        let result = StorageVec::<i32, 3>::try_from(&[1, 2, 3, 4][..]);
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(result, Err(TryFromSliceError(())));
+        #[cfg(feature = "alloc")]
+        assert_eq!(result.unwrap().as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn push_within_capacity_stops_at_capacity() {
+        let mut list: StorageVec<i32, 4> = StorageVec::with_capacity(4);
+        let capacity = list.capacity();
+        for _ in 0..capacity {
+            assert_eq!(list.push_within_capacity(0), Ok(()));
+        }
+        assert_eq!(list.push_within_capacity(-1), Err(-1));
+        assert_eq!(list.len(), capacity);
+        assert_eq!(list.capacity(), capacity);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn leak_keeps_contents() {
+        let leaked: &'static mut [i32] = list::<2>(&[1, 2, 3]).leak();
+        assert_eq!(leaked, &[1, 2, 3]);
+        leaked[0] = 10;
+        assert_eq!(leaked, &[10, 2, 3]);
+    }
+
+    #[test]
+    fn map_changes_element_type() {
+        let bytes: StorageVec<u8, 4> = [1u8, 2, 255].iter().copied().collect();
+        let wide: StorageVec<u32, 4> = bytes.map(|b| u32::from(b) * 1000);
+        assert_eq!(wide.as_slice(), &[1000, 2000, 255_000]);
+    }
+
+    #[test]
+    fn into_iterator_for_references() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        for item in &mut list {
+            *item += 1;
+        }
+        let mut sum = 0;
+        for item in &list {
+            sum += *item;
+        }
+        assert_eq!(sum, 9);
+    }
+
+    #[test]
+    fn resize_default_grows_and_shrinks() {
+        let mut list = list::<4>(&[1]);
+        list.resize_default(3);
+        assert_eq!(list.as_slice(), &[1, 0, 0]);
+        list.resize_default(1);
+        assert_eq!(list.as_slice(), &[1]);
+        let result = list.try_resize_default(5);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(result, Err(()));
+            assert_eq!(list.as_slice(), &[1]);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(result, Ok(()));
+            assert_eq!(list.as_slice(), &[1, 0, 0, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn as_ptr_points_at_first_element() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.as_ptr(), core::ptr::from_ref(&list[0]));
+        let first = core::ptr::from_mut(&mut list[0]);
+        assert_eq!(list.as_mut_ptr(), first);
+    }
+
+    #[test]
+    fn extract_if_yields_matches_and_keeps_the_rest() {
+        let mut list = list::<8>(&[1, 2, 3, 4, 5, 6]);
+        let extracted: std::vec::Vec<i32> = list
+            .extract_if(|x| {
+                *x *= 10;
+                *x % 20 == 0
+            })
+            .collect();
+        assert_eq!(extracted, [20, 40, 60]);
+        assert_eq!(list.as_slice(), &[10, 30, 50]);
+    }
+
+    #[test]
+    fn extract_if_removes_matches_when_dropped_early() {
+        let mut list = list::<8>(&[1, 2, 3, 4]);
+        {
+            let mut extracted = list.extract_if(|x| *x > 1);
+            assert_eq!(extracted.size_hint(), (3, Some(3)));
+            assert_eq!(extracted.next(), Some(2));
+        }
+        assert_eq!(list.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn flatten_sub_lists() {
+        let nested: StorageVec<StorageVec<i32, 2>, 3> =
+            [list::<2>(&[1, 2]), list::<2>(&[]), list::<2>(&[3])]
+                .iter()
+                .cloned()
+                .collect();
+        let mut out = list::<4>(&[0]);
+        nested.flatten_into(&mut out);
+        assert_eq!(out.as_slice(), &[0, 1, 2, 3]);
+
+        let result = nested.try_flatten_into(&mut out);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(result, Err(()));
+            assert_eq!(out.as_slice(), &[0, 1, 2, 3]);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(result, Ok(()));
+            assert_eq!(out.as_slice(), &[0, 1, 2, 3, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn remove_item_first_occurrence() {
+        let mut list = list::<8>(&[1, 2, 3, 2]);
+        assert_eq!(list.remove_item(&2), Some(2));
+        assert_eq!(list.as_slice(), &[1, 3, 2]);
+        assert_eq!(list.remove_item(&4), None);
+        assert_eq!(list.as_slice(), &[1, 3, 2]);
+    }
+
+    #[test]
+    fn fmt_write_pushes_utf8_bytes() {
+        use core::fmt::Write;
+
+        let mut list: StorageVec<u8, 4> = StorageVec::new();
+        write!(list, "{}", 42).unwrap();
+        assert_eq!(list.as_slice(), b"42");
+        let result = write!(list, "{}", 345);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(result, Err(core::fmt::Error));
+            assert_eq!(list.as_slice(), b"42");
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(result, Ok(()));
+            assert_eq!(list.as_slice(), b"42345");
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_write_round_trip() {
+        use std::io::Write;
+
+        let mut list: StorageVec<u8, 4> = StorageVec::new();
+        list.write_all(b"abc").unwrap();
+        list.flush().unwrap();
+        assert_eq!(list.as_slice(), b"abc");
+
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(list.write(b"de").unwrap(), 1);
+            let err = list.write_all(b"f").unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+            assert_eq!(list.as_slice(), b"abcd");
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(list.write(b"de").unwrap(), 2);
+            assert_eq!(list.as_slice(), b"abcde");
+        }
+    }
+
+    #[test]
+    fn splice_replaces_middle_with_longer_sequence() {
+        let mut l = list::<8>(&[1, 2, 3, 4]);
+        let removed: std::vec::Vec<i32> = l.splice(1..3, [7, 8, 9]).collect();
+        assert_eq!(removed, [2, 3]);
+        assert_eq!(&*l, &[1, 7, 8, 9, 4]);
+    }
+
+    #[test]
+    fn splice_replaces_middle_with_shorter_sequence() {
+        let mut l = list::<8>(&[1, 2, 3, 4, 5]);
+        let removed: std::vec::Vec<i32> = l.splice(1..4, [6]).collect();
+        assert_eq!(removed, [2, 3, 4]);
+        assert_eq!(&*l, &[1, 6, 5]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn try_splice_overflow_leaves_list_untouched() {
+        let mut l = list::<4>(&[1, 2, 3]);
+        assert!(l.try_splice(1..2, [7, 8, 9]).is_err());
+        assert_eq!(&*l, &[1, 2, 3]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic(expected = "capacity overflow: len 2 == capacity 2")]
+    fn push_onto_full_list_reports_len_and_capacity() {
+        let mut list = list::<2>(&[1, 2]);
+        list.push(3);
+    }
+
+    #[cfg(all(feature = "alloc", feature = "stack"))]
+    #[test]
+    fn push_onto_full_stack_list_spills() {
+        let mut list = list::<2>(&[1, 2]);
+        assert!(list.is_inline());
+        list.push(3);
+        assert!(!list.is_inline());
+        assert_eq!(&*list, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn into_array_when_full() {
+        assert_eq!(list::<3>(&[1, 2, 3]).into_array(), Ok([1, 2, 3]));
+    }
+
+    #[test]
+    fn into_array_when_under_full() {
+        let err = list::<3>(&[1, 2]).into_array().unwrap_err();
+        assert_eq!(&*err, &[1, 2]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn into_array_when_over_full() {
+        let err = list::<3>(&[1, 2, 3, 4]).into_array().unwrap_err();
+        assert_eq!(&*err, &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn pop_if_matching_last_item() {
+        let mut list = list::<4>(&[1, 2, 4]);
+        assert_eq!(list.pop_if(|x| x % 2 == 0), Some(4));
+        assert_eq!(&*list, &[1, 2]);
+    }
+
+    #[test]
+    fn pop_if_non_matching_last_item() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.pop_if(|x| x % 2 == 0), None);
+        assert_eq!(&*list, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn pop_if_on_empty_list() {
+        let mut list = StorageVec::<i32, 4>::new();
+        assert_eq!(list.pop_if(|_| true), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn iter_indexed_pairs_indices_with_items() {
+        let mut list = list::<4>(&[5, 6, 7]);
+        let pairs: std::vec::Vec<(usize, &i32)> = list.iter_indexed().collect();
+        assert_eq!(pairs, [(0, &5), (1, &6), (2, &7)]);
+
+        list.iter_indexed_mut()
+            .for_each(|(i, x)| *x += i32::try_from(i).unwrap());
+        assert_eq!(&*list, &[5, 7, 9]);
+    }
+
+    #[test]
+    fn fill_from_iter_shorter_than_remaining_capacity() {
+        let mut list = list::<4>(&[1]);
+        assert_eq!(list.fill_from_iter([2, 3]), 2);
+        assert_eq!(&*list, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn fill_from_iter_longer_than_remaining_capacity() {
+        let mut list = list::<4>(&[1, 2]);
+        let pushed = list.fill_from_iter(3..=6);
+        #[cfg(not(feature = "alloc"))]
+        {
+            assert_eq!(pushed, 2);
+            assert_eq!(&*list, &[1, 2, 3, 4]);
+        }
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(pushed, 4);
+            assert_eq!(&*list, &[1, 2, 3, 4, 5, 6]);
+        }
+    }
+
+    #[test]
+    fn split_and_rsplit_on_separator() {
+        let list = list::<8>(&[1, 0, 2, 3, 0, 4]);
+        let parts: std::vec::Vec<&[i32]> = list.split(|&x| x == 0).collect();
+        assert_eq!(parts, [&[1][..], &[2, 3], &[4]]);
+        let parts: std::vec::Vec<&[i32]> = list.rsplit(|&x| x == 0).collect();
+        assert_eq!(parts, [&[4][..], &[2, 3], &[1]]);
+    }
+
+    #[test]
+    fn starts_with_and_ends_with() {
+        let list = list::<4>(&[1, 2, 3]);
+        assert!(list.starts_with(&[1, 2]));
+        assert!(!list.starts_with(&[2, 3]));
+        assert!(list.ends_with(&[2, 3]));
+        assert!(!list.ends_with(&[1, 2]));
+        assert!(list.starts_with(&[]));
+        assert!(!list.ends_with(&[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn try_with_capacity_within_n() {
+        let list = StorageVec::<i32, 4>::try_with_capacity(4).unwrap();
+        assert!(list.is_empty());
+        assert!(list.capacity() >= 4);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn try_with_capacity_past_n_is_an_error() {
+        assert!(StorageVec::<i32, 4>::try_with_capacity(5).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn try_with_capacity_past_n_allocates() {
+        let list = StorageVec::<i32, 4>::try_with_capacity(16).unwrap();
+        assert!(list.is_empty());
+        assert!(list.capacity() >= 16);
+    }
+
+    #[test]
+    fn repeat_small_pattern() {
+        let pattern = list::<6>(&[1, 2]);
+        let repeated = pattern.repeat(3);
+        assert_eq!(repeated.len(), 6);
+        assert_eq!(&*repeated, &[1, 2, 1, 2, 1, 2]);
+        assert!(pattern.repeat(0).is_empty());
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn try_repeat_past_capacity_is_an_error() {
+        assert!(list::<6>(&[1, 2]).try_repeat(4).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn repeat_past_n_grows() {
+        let repeated = list::<2>(&[1, 2]).repeat(3);
+        assert_eq!(&*repeated, &[1, 2, 1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn get_or_in_and_out_of_bounds() {
+        let list = list::<4>(&[1, 2, 3]);
+        assert_eq!(*list.get_or(1, &0), 2);
+        assert_eq!(*list.get_or(3, &0), 0);
+    }
+
+    #[test]
+    fn split_last_chunk_below_at_and_above_m() {
+        let short = list::<4>(&[1]);
+        assert_eq!(short.split_last_chunk::<2>(), None);
+
+        let exact = list::<4>(&[1, 2]);
+        assert_eq!(exact.split_last_chunk::<2>(), Some((&[][..], &[1, 2])));
+
+        let long = list::<4>(&[1, 2, 3, 4]);
+        assert_eq!(long.split_last_chunk::<2>(), Some((&[1, 2][..], &[3, 4])));
+    }
+
+    #[test]
+    fn shift_insert_keeps_order() {
+        let mut list = list::<4>(&[1, 2, 3]);
+        assert_eq!(list.shift_insert(1, 9), Ok(()));
+        assert_eq!(&*list, &[1, 9, 2, 3]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn shift_insert_into_full_list_is_an_error() {
+        let mut list = list::<3>(&[1, 2, 3]);
+        assert_eq!(list.shift_insert(0, 9), Err(9));
+        assert_eq!(&*list, &[1, 2, 3]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn try_reserve_reports_allocation_failure() {
+        let mut list = list::<4>(&[1]);
+        // this fits in a `Layout`, but no allocator can provide it
+        assert_eq!(
+            list.try_reserve(usize::MAX / 8 - 1),
+            Err(TryReserveError::AllocError)
+        );
+        assert_eq!(list.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn extract_if_panic_keeps_unextracted_items() {
+        let mut list = list::<8>(&[1, 2, 3, 4, 5]);
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            list.extract_if(|x| {
+                assert!(*x != 4, "predicate panicked");
+                *x % 2 == 0
+            })
+            .count()
+        }));
+        assert!(result.is_err());
+        assert_eq!(&*list, &[1, 3, 4, 5]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn extract_if_keeps_the_buffer() {
+        let mut list = list::<2>(&[1, 2, 3, 4]);
+        let capacity = list.capacity();
+        assert_eq!(list.extract_if(|x| *x > 2).count(), 2);
+        assert_eq!(list.capacity(), capacity);
+        assert_eq!(&*list, &[1, 2]);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let list: StorageVec<i32, 4> = [1, 2, 3].iter().copied().collect();
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        let back: StorageVec<i32, 4> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, list);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn over_capacity_is_an_error() {
+        use std::string::ToString;
+
+        let err = serde_json::from_str::<StorageVec<i32, 2>>("[1,2,3]").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid length 3, expected a sequence of at most 2 elements"));
     }
 }